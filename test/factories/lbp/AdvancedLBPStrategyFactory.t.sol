@@ -31,7 +31,6 @@ contract AdvancedLBPStrategyFactoryTest is Test {
     address constant PERMIT2 = 0x000000000022D473030F116dDEE9F6B43aC78BA3;
     address constant POSITION_MANAGER = 0xbD216513d74C8cf14cf4747E6AaA6420FF64ee9e;
     address constant POOL_MANAGER = 0x000000000004444c5dc75cB358380D2e3dE08A90;
-    uint160 constant BEFORE_INITIALIZE_FLAG_MASK = 1 << 13;
 
     AdvancedLBPStrategyFactory public factory;
     MockERC20 token;
@@ -93,10 +92,9 @@ contract AdvancedLBPStrategyFactoryTest is Test {
                 )
             )
         );
-        address poolMask = address(BEFORE_INITIALIZE_FLAG_MASK);
-        bytes32 topLevelSalt = new SaltGenerator().withInitCodeHash(initCodeHash).withMask(poolMask)
-            .withMsgSender(address(this)).withTokenLauncher(address(liquidityLauncher))
-            .withStrategyFactoryAddress(address(factory)).generate();
+        (bytes32 topLevelSalt, address predictedAddress,) = new SaltGenerator().withInitCodeHash(initCodeHash)
+            .withStrategyKind("advanced").withMsgSender(address(this)).withTokenLauncher(address(liquidityLauncher))
+            .withStrategyFactoryAddress(address(factory)).generateWithAddress();
 
         address expectedAddress = factory.getAddress(
             address(token),
@@ -117,6 +115,7 @@ contract AdvancedLBPStrategyFactoryTest is Test {
         );
 
         assertEq(address(lbp), expectedAddress);
+        assertEq(address(lbp), predictedAddress);
         assertEq(lbp.totalSupply(), TOTAL_SUPPLY);
         assertEq(lbp.token(), address(token));
         assertEq(address(lbp.positionManager()), POSITION_MANAGER);
@@ -144,8 +143,7 @@ contract AdvancedLBPStrategyFactoryTest is Test {
                 )
             )
         );
-        address poolMask = address(BEFORE_INITIALIZE_FLAG_MASK);
-        bytes32 topLevelSalt = new SaltGenerator().withInitCodeHash(initCodeHash).withMask(poolMask)
+        bytes32 topLevelSalt = new SaltGenerator().withInitCodeHash(initCodeHash).withStrategyKind("advanced")
             .withMsgSender(address(this)).withTokenLauncher(address(liquidityLauncher))
             .withStrategyFactoryAddress(address(factory)).generate();
 
@@ -8,11 +8,15 @@ import {console} from "forge-std/console.sol";
 ///
 /// Example usage:
 /// bytes32 salt = SaltGenerator.init()
-///                        .withMask(SOME_MASK)
+///                        .withMask(SOME_MASK) // or .withStrategyKind("advanced")
 ///                        .withMsgSender(the sender of the tx)
 ///                        .withStrategyAddress(the strategy being used - e.g. deployed lbpBasic)
 ///                        .withTokenLauncher(the address of the token launcher)
-///                        .generate();
+///                        .withSeed(some seed) // optional, for reproducible salts
+///                        .withTimeout(some seconds) // optional, DEFAULT_TIMEOUT otherwise
+///                        .generate(); // or .generateWithAddress() to also get the strategy address
+///
+/// .generateBatch(n) mines n distinct salts in a single call, e.g. for fuzz tests
 ///
 /// Under the hood it calls a program which will mine an address for you
 /// Warning: before usage ensure that the binary has been built
@@ -22,9 +26,24 @@ contract SaltGenerator {
     address $tokenLauncher;
     address $mask;
     bytes32 $initCodeHash;
+    string $strategyKind;
+    address $token;
+    uint256 $totalSupply;
+    bytes $configData;
+    address $positionManager;
+    address $poolManager;
+    bytes32 $seed;
+    bool $hasSeed;
+    uint256 $timeout = DEFAULT_TIMEOUT;
 
     Vm public constant vm = Vm(address(bytes20(uint160(uint256(keccak256("hevm cheat code"))))));
 
+    /// @notice Salts found by previous runs, reused as long as the inputs and the strategy bytecode do not change
+    string constant CACHE_FILE = "test/saltGenerator/addressMiner/target/salt-cache.json";
+
+    /// @notice Seconds after which the miner gives up with exit code 3, failing the ffi call instead of hanging the test
+    uint256 constant DEFAULT_TIMEOUT = 600;
+
     constructor() {}
 
     function withMask(address _mask) public returns (SaltGenerator) {
@@ -32,6 +51,13 @@ contract SaltGenerator {
         return this;
     }
 
+    /// @notice Use the hook permissions of a strategy preset (full-range, advanced, governed, virtual-governed)
+    /// @dev Cannot be combined with a mask
+    function withStrategyKind(string memory _strategyKind) public returns (SaltGenerator) {
+        $strategyKind = _strategyKind;
+        return this;
+    }
+
     function withMsgSender(address _msgSender) public returns (SaltGenerator) {
         $msgSender = _msgSender;
         return this;
@@ -52,19 +78,136 @@ contract SaltGenerator {
         return this;
     }
 
+    /// @notice Compute the init code hash from the forge artifacts of the strategy instead of passing it in
+    /// @dev Requires a strategy kind and cannot be combined with an init code hash
+    function withLaunchParams(
+        address _token,
+        uint256 _totalSupply,
+        bytes memory _configData,
+        address _positionManager,
+        address _poolManager
+    ) public returns (SaltGenerator) {
+        $token = _token;
+        $totalSupply = _totalSupply;
+        $configData = _configData;
+        $positionManager = _positionManager;
+        $poolManager = _poolManager;
+        return this;
+    }
+
+    /// @notice Search from a fixed seed so the same inputs always return the same salt
+    function withSeed(bytes32 _seed) public returns (SaltGenerator) {
+        $seed = _seed;
+        $hasSeed = true;
+        return this;
+    }
+
+    /// @notice Give up mining after the given number of seconds
+    function withTimeout(uint256 _timeout) public returns (SaltGenerator) {
+        $timeout = _timeout;
+        return this;
+    }
+
     function generate() public returns (bytes32) {
-        string[] memory ffi_cmds = new string[](10);
+        string[] memory ffi_cmds = _appendOption(_ffiCommands(), "--cache", CACHE_FILE);
+        ffi_cmds = _append(ffi_cmds, "-q"); // quiet mode to not pollute stdout
+        return abi.decode(vm.ffi(ffi_cmds), (bytes32));
+    }
+
+    /// @notice Generate a salt along with the strategy address it deploys to
+    /// @return salt The salt to pass to the token launcher
+    /// @return predicted The address of the strategy deployed with the salt
+    /// @return create2Salt The salt the strategy factory deploys the strategy with
+    function generateWithAddress() public returns (bytes32 salt, address predicted, bytes32 create2Salt) {
+        string[] memory ffi_cmds = _appendOption(_ffiCommands(), "--cache", CACHE_FILE);
+        ffi_cmds = _appendOption(ffi_cmds, "--format", "abi");
+        return abi.decode(vm.ffi(ffi_cmds), (bytes32, address, bytes32));
+    }
+
+    /// @notice Generate distinct salts in a single run of the miner, the lowest matching ones after the seed
+    /// @dev Not cached, the miner only caches a single salt per set of inputs
+    /// @return salts The salts to pass to the token launcher
+    /// @return predicted The addresses of the strategies deployed with the salts
+    function generateBatch(uint256 count) public returns (bytes32[] memory salts, address[] memory predicted) {
+        string[] memory ffi_cmds = _appendOption(_ffiCommands(), "--count", vm.toString(count));
+        ffi_cmds = _appendOption(ffi_cmds, "--format", "abi");
+        (salts, predicted,) = abi.decode(vm.ffi(ffi_cmds), (bytes32[], address[], bytes32[]));
+    }
+
+    function _ffiCommands() internal view returns (string[] memory ffi_cmds) {
+        ffi_cmds = new string[](1);
         ffi_cmds[0] = "test/saltGenerator/run.sh";
-        ffi_cmds[1] = vm.toString($initCodeHash);
-        ffi_cmds[2] = vm.toString($mask);
-        ffi_cmds[3] = "-m";
-        ffi_cmds[4] = vm.toString($msgSender);
-        ffi_cmds[5] = "-s";
-        ffi_cmds[6] = vm.toString($strategyFactoryAddress);
-        ffi_cmds[7] = "-l";
-        ffi_cmds[8] = vm.toString($tokenLauncher);
-        ffi_cmds[9] = "-q"; // quiet mode to not pollute stdout
+        if ($initCodeHash != bytes32(0)) {
+            ffi_cmds = _append(ffi_cmds, vm.toString($initCodeHash));
+        } else {
+            ffi_cmds = _appendOption(ffi_cmds, "--token", vm.toString($token));
+            ffi_cmds = _appendOption(ffi_cmds, "--total-supply", vm.toString($totalSupply));
+            ffi_cmds = _appendOption(ffi_cmds, "--config-data", vm.toString($configData));
+            ffi_cmds = _appendOption(ffi_cmds, "--position-manager", vm.toString($positionManager));
+            ffi_cmds = _appendOption(ffi_cmds, "--pool-manager", vm.toString($poolManager));
+        }
+        ffi_cmds = _appendOption(ffi_cmds, "-m", vm.toString($msgSender));
+        ffi_cmds = _appendOption(ffi_cmds, "-s", vm.toString($strategyFactoryAddress));
+        ffi_cmds = _appendOption(ffi_cmds, "-l", vm.toString($tokenLauncher));
+        if ($hasSeed) {
+            ffi_cmds = _appendOption(ffi_cmds, "--seed", vm.toString($seed));
+        }
+        ffi_cmds = _appendOption(ffi_cmds, "--timeout", vm.toString($timeout));
+        if (bytes($strategyKind).length > 0) {
+            ffi_cmds = _appendOption(ffi_cmds, "-k", $strategyKind);
+        }
+        string[] memory permissionFlags = _permissionFlags();
+        for (uint256 i = 0; i < permissionFlags.length; i++) {
+            ffi_cmds = _append(ffi_cmds, permissionFlags[i]);
+        }
+    }
 
-        return abi.decode(vm.ffi(ffi_cmds), (bytes32));
+    function _append(string[] memory args, string memory arg) internal pure returns (string[] memory newArgs) {
+        newArgs = new string[](args.length + 1);
+        for (uint256 i = 0; i < args.length; i++) {
+            newArgs[i] = args[i];
+        }
+        newArgs[args.length] = arg;
+    }
+
+    function _appendOption(string[] memory args, string memory name, string memory value)
+        internal
+        pure
+        returns (string[] memory)
+    {
+        return _append(_append(args, name), value);
+    }
+
+    /// @notice Converts the mask into the named hook permission flags accepted by the address miner
+    function _permissionFlags() internal view returns (string[] memory flags) {
+        // Indexed by bit position, see the flag constants in v4-core Hooks.sol
+        string[14] memory names = [
+            string("--after-remove-liquidity-return-delta"),
+            "--after-add-liquidity-return-delta",
+            "--after-swap-return-delta",
+            "--before-swap-return-delta",
+            "--after-donate",
+            "--before-donate",
+            "--after-swap",
+            "--before-swap",
+            "--after-remove-liquidity",
+            "--before-remove-liquidity",
+            "--after-add-liquidity",
+            "--before-add-liquidity",
+            "--after-initialize",
+            "--before-initialize"
+        ];
+        uint256 mask = uint160($mask);
+
+        uint256 count;
+        for (uint256 i = 0; i < names.length; i++) {
+            if (mask & (1 << i) != 0) count++;
+        }
+
+        flags = new string[](count);
+        count = 0;
+        for (uint256 i = 0; i < names.length; i++) {
+            if (mask & (1 << i) != 0) flags[count++] = names[i];
+        }
     }
 }
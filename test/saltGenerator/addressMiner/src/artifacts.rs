@@ -0,0 +1,54 @@
+use alloy_primitives::{hex, Bytes};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Errors returned when loading a forge artifact
+#[derive(Debug)]
+pub enum ArtifactError {
+    Io(PathBuf, std::io::Error),
+    Json(PathBuf, serde_json::Error),
+    MissingBytecode(PathBuf),
+    UnlinkedLibraries(PathBuf),
+    InvalidBytecode(PathBuf, hex::FromHexError),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactError::Io(path, err) => write!(f, "failed to read {}: {}", path.display(), err),
+            ArtifactError::Json(path, err) => write!(f, "failed to parse {}: {}", path.display(), err),
+            ArtifactError::MissingBytecode(path) => write!(f, "{} has no creation bytecode", path.display()),
+            ArtifactError::UnlinkedLibraries(path) => {
+                write!(f, "{} has unlinked library references", path.display())
+            }
+            ArtifactError::InvalidBytecode(path, err) => {
+                write!(f, "{} has invalid creation bytecode: {}", path.display(), err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+/// Returns the path forge writes the artifact of a contract to, e.g. out/<Contract>.sol/<Contract>.json
+pub fn artifact_path(out_dir: &Path, contract_name: &str) -> PathBuf {
+    out_dir.join(format!("{contract_name}.sol")).join(format!("{contract_name}.json"))
+}
+
+/// Loads the creation bytecode of a contract from the forge build output directory
+pub fn load_creation_code(out_dir: &Path, contract_name: &str) -> Result<Bytes, ArtifactError> {
+    let path = artifact_path(out_dir, contract_name);
+    let contents = std::fs::read_to_string(&path).map_err(|err| ArtifactError::Io(path.clone(), err))?;
+    let artifact: serde_json::Value =
+        serde_json::from_str(&contents).map_err(|err| ArtifactError::Json(path.clone(), err))?;
+
+    let object = artifact["bytecode"]["object"]
+        .as_str()
+        .filter(|object| !object.is_empty() && *object != "0x")
+        .ok_or_else(|| ArtifactError::MissingBytecode(path.clone()))?;
+    // Library placeholders are of the form __$<hash>$__ and cannot be hashed
+    if object.contains("__$") {
+        return Err(ArtifactError::UnlinkedLibraries(path));
+    }
+    hex::decode(object).map(Bytes::from).map_err(|err| ArtifactError::InvalidBytecode(path, err))
+}
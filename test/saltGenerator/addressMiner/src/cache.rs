@@ -0,0 +1,253 @@
+use crate::derivation::SaltDerivation;
+use crate::hooks::HookPermissions;
+use crate::strategy::StrategyKind;
+use crate::vanity::Vanity;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+#[derive(Debug)]
+pub enum CacheError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for CacheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheError::Io(path, err) => write!(f, "Failed to access salt cache {}: {}", path.display(), err),
+            CacheError::Json(path, err) => write!(f, "Failed to parse salt cache {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for CacheError {}
+
+/// The launch parameters the init code hash is computed from, all unset if the init code hash is given
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchInputs {
+    pub strategy_kind: Option<StrategyKind>,
+    pub token: Option<Address>,
+    pub total_supply: Option<U256>,
+    pub config_data_hash: Option<B256>,
+}
+
+impl LaunchInputs {
+    pub fn new(
+        strategy_kind: Option<StrategyKind>,
+        token: Option<Address>,
+        total_supply: Option<U256>,
+        config_data: Option<&Bytes>,
+    ) -> Self {
+        Self { strategy_kind, token, total_supply, config_data_hash: config_data.map(keccak256) }
+    }
+}
+
+/// The mining inputs a cached salt was found for, apart from the init code hash
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheKey {
+    pub strategy_address: Address,
+    pub wrapping_senders: Vec<Address>,
+    pub hook_flags: u16,
+    /// The vanity requirements as displayed by `Vanity`
+    pub vanity: String,
+    pub seed: Option<B256>,
+    #[serde(flatten)]
+    pub launch: LaunchInputs,
+}
+
+impl CacheKey {
+    pub fn new(
+        strategy_address: Address,
+        derivation: &SaltDerivation,
+        hook_permissions: HookPermissions,
+        vanity: &Vanity,
+        seed: Option<B256>,
+        launch: LaunchInputs,
+    ) -> Self {
+        Self {
+            strategy_address,
+            wrapping_senders: derivation.senders().to_vec(),
+            hook_flags: hook_permissions.flags(),
+            vanity: vanity.to_string(),
+            seed,
+            launch,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    #[serde(flatten)]
+    key: CacheKey,
+    init_code_hash: B256,
+    salt: B256,
+}
+
+/// Salts found by previous runs, stored as JSON.
+/// Each key holds the salt of its latest init code hash. The key includes the token and config data, so forge tests
+/// differing only in those each keep their salt, while a salt of changed bytecode is evicted by the next one.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SaltCache {
+    entries: Vec<CacheEntry>,
+}
+
+impl SaltCache {
+    /// Loads the cache from a file, empty if the file does not exist yet
+    pub fn load(path: &Path) -> Result<Self, CacheError> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|err| CacheError::Json(path.to_path_buf(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(CacheError::Io(path.to_path_buf(), err)),
+        }
+    }
+
+    /// Writes the cache to a temporary file next to the path and renames it over the path,
+    /// so concurrent runs never read a partially written cache
+    pub fn save(&self, path: &Path) -> Result<(), CacheError> {
+        let json = serde_json::to_string_pretty(self).map_err(|err| CacheError::Json(path.to_path_buf(), err))?;
+        write_atomically(path, &json).map_err(|(path, err)| CacheError::Io(path, err))
+    }
+
+    /// Returns the cached salt for the inputs, it still needs to be checked against the requirements
+    pub fn get(&self, key: &CacheKey, init_code_hash: B256) -> Option<B256> {
+        self.entries
+            .iter()
+            .find(|entry| entry.key == *key && entry.init_code_hash == init_code_hash)
+            .map(|entry| entry.salt)
+    }
+
+    /// Caches the salt for the inputs, replacing the one cached for the same key whatever its init code hash
+    pub fn insert(&mut self, key: CacheKey, init_code_hash: B256, salt: B256) {
+        self.entries.retain(|entry| entry.key != key);
+        self.entries.push(CacheEntry { key, init_code_hash, salt });
+    }
+
+    /// Caches the salt in the file at the path, merged into the salts other runs saved in the meantime.
+    /// Parallel runs take turns holding an exclusive lock on `<path>.lock` from reading the file to renaming the new
+    /// one over it, so none of their salts is lost. A file that does not parse is replaced.
+    pub fn insert_and_save(path: &Path, key: CacheKey, init_code_hash: B256, salt: B256) -> Result<(), CacheError> {
+        let _lock = lock(path)?;
+        let mut cache = match Self::load(path) {
+            Err(CacheError::Json(..)) => Self::default(),
+            cache => cache?,
+        };
+        cache.insert(key, init_code_hash, salt);
+        cache.save(path)
+    }
+}
+
+/// Locks `<path>.lock` until the returned file is dropped, creating the directory if needed
+fn lock(path: &Path) -> Result<fs::File, CacheError> {
+    let mut lock_path = path.as_os_str().to_owned();
+    lock_path.push(".lock");
+    let lock_path = PathBuf::from(lock_path);
+    if let Some(dir) = lock_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| CacheError::Io(dir.to_path_buf(), err))?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|err| CacheError::Io(lock_path.clone(), err))?;
+    file.lock().map_err(|err| CacheError::Io(lock_path, err))?;
+    Ok(file)
+}
+
+/// Writes the contents to a temporary file next to the path and renames it over the path, creating the directory if
+/// needed. Returns the path that could not be written on failure.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<(), (PathBuf, io::Error)> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|err| (dir.to_path_buf(), err))?;
+    }
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = PathBuf::from(tmp_path);
+    fs::write(&tmp_path, contents).map_err(|err| (tmp_path.clone(), err))?;
+    fs::rename(&tmp_path, path).map_err(|err| (path.to_path_buf(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn key() -> CacheKey {
+        CacheKey {
+            strategy_address: Address::repeat_byte(0x11),
+            wrapping_senders: vec![Address::repeat_byte(0x22)],
+            hook_flags: 0x2000,
+            vanity: "none".to_string(),
+            seed: None,
+            launch: LaunchInputs::new(
+                Some(StrategyKind::FullRange),
+                Some(Address::repeat_byte(0x33)),
+                Some(U256::from(1_000_000)),
+                Some(&Bytes::from_static(&[0xde, 0xad])),
+            ),
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("address-miner-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("salt-cache.json")
+    }
+
+    #[test]
+    fn insert_evicts_the_salt_of_a_changed_init_code_hash() {
+        let (hash_a, hash_b) = (B256::repeat_byte(0xaa), B256::repeat_byte(0xbb));
+        let mut cache = SaltCache::default();
+        cache.insert(key(), hash_a, B256::with_last_byte(1));
+        cache.insert(key(), hash_b, B256::with_last_byte(2));
+        assert_eq!(cache.get(&key(), hash_a), None);
+        assert_eq!(cache.get(&key(), hash_b), Some(B256::with_last_byte(2)));
+        assert_eq!(cache.entries.len(), 1);
+
+        // Launches differing in their token or config data keep their own salt
+        let other_token =
+            CacheKey { launch: LaunchInputs { token: Some(Address::repeat_byte(0x44)), ..key().launch }, ..key() };
+        cache.insert(other_token.clone(), hash_a, B256::with_last_byte(3));
+        assert_eq!(cache.get(&other_token, hash_a), Some(B256::with_last_byte(3)));
+        assert_eq!(cache.get(&key(), hash_b), Some(B256::with_last_byte(2)));
+
+        let other_config_data = LaunchInputs::new(
+            Some(StrategyKind::FullRange),
+            Some(Address::repeat_byte(0x33)),
+            Some(U256::from(1_000_000)),
+            Some(&Bytes::from_static(&[0xbe, 0xef])),
+        );
+        assert_eq!(cache.get(&CacheKey { launch: other_config_data, ..key() }, hash_b), None);
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[test]
+    fn parallel_runs_keep_all_salts() {
+        let path = temp_path("parallel");
+        // A launch of its own token per run
+        let keyed = |index: u8| CacheKey {
+            launch: LaunchInputs { token: Some(Address::with_last_byte(index)), ..key().launch },
+            ..key()
+        };
+        thread::scope(|scope| {
+            for index in 0..16u8 {
+                let path = &path;
+                let hash = B256::with_last_byte(index);
+                scope.spawn(move || SaltCache::insert_and_save(path, keyed(index), hash, hash).unwrap());
+            }
+        });
+
+        let cache = SaltCache::load(&path).unwrap();
+        for index in 0..16u8 {
+            let hash = B256::with_last_byte(index);
+            assert_eq!(cache.get(&keyed(index), hash), Some(hash));
+        }
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
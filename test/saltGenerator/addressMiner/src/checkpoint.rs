@@ -0,0 +1,70 @@
+use crate::cache::{write_atomically, CacheKey};
+use crate::miner::Shard;
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(path, err) => write!(f, "Failed to access checkpoint {}: {}", path.display(), err),
+            CheckpointError::Json(path, err) => write!(f, "Failed to parse checkpoint {}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+/// The progress of a search through a shard, stored as JSON so an interrupted search can resume from it.
+/// The seed of the key is always set, a search without one resumes with the seed of the checkpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    #[serde(flatten)]
+    pub key: CacheKey,
+    pub init_code_hash: B256,
+    pub shard: Shard,
+    /// The first block of the shard not searched yet
+    pub next_block: u64,
+    /// Number of salts hashed by all the runs so far
+    pub attempts: u64,
+}
+
+impl Checkpoint {
+    /// Loads the checkpoint from a file, `None` if the file does not exist yet
+    pub fn load(path: &Path) -> Result<Option<Self>, CheckpointError> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map(Some).map_err(|err| CheckpointError::Json(path.to_path_buf(), err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(CheckpointError::Io(path.to_path_buf(), err)),
+        }
+    }
+
+    /// Writes the checkpoint atomically, so an interrupted write never loses the previous one
+    pub fn save(&self, path: &Path) -> Result<(), CheckpointError> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|err| CheckpointError::Json(path.to_path_buf(), err))?;
+        write_atomically(path, &json).map_err(|(path, err)| CheckpointError::Io(path, err))
+    }
+
+    /// Removes the checkpoint file of a finished search, if any
+    pub fn remove(path: &Path) -> Result<(), CheckpointError> {
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(CheckpointError::Io(path.to_path_buf(), err)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns true if the checkpoint was written by a search of the same inputs, of any seed if the key has none
+    pub fn resumes(&self, key: &CacheKey, init_code_hash: B256, shard: Shard) -> bool {
+        let key = CacheKey { seed: key.seed.or(self.key.seed), ..key.clone() };
+        self.key == key && self.init_code_hash == init_code_hash && self.shard == shard
+    }
+}
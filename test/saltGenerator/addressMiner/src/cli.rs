@@ -0,0 +1,539 @@
+use address_miner::artifacts::load_creation_code;
+use address_miner::cache::LaunchInputs;
+use address_miner::derivation::SaltDerivation;
+use address_miner::hooks::HookPermissions;
+use address_miner::manifest::{load_manifest, ManifestJob};
+use address_miner::miner::{SearchError, SearchLimits, Shard};
+use address_miner::score::ScoreObjective;
+use address_miner::STRATEGY_POOL_FEE;
+use address_miner::strategy::{LaunchParams, StrategyError, StrategyKind};
+use address_miner::types::{AdvancedConfigData, ConfigData, LBPInitializationParams, MigratorParameters};
+use address_miner::vanity::Vanity;
+use alloy_primitives::aliases::{I24, U24};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Parser)]
+#[command(about, long_about = None, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub mine: MineArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Mine a salt for a strategy address with the given hook permissions (default)
+    Mine(MineArgs),
+    /// Predict the address StrategyFactory.getAddress returns for a launch
+    Predict(PredictArgs),
+    /// Verify that a salt results in a strategy address with the given hook permissions and vanity
+    Verify(VerifyArgs),
+    /// Compute the MigrationData an LBP strategy migrates with for an auction outcome
+    SimulateMigration(SimulateMigrationArgs),
+}
+
+#[derive(Args)]
+pub struct MineArgs {
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+    #[arg(
+        short = 't',
+        long,
+        value_name = "NUMBER_OF_THREADS",
+        default_value_t = NonZeroUsize::new(8).unwrap()
+    )]
+    pub threads: NonZeroUsize,
+    /// Returns the same salt for the same inputs on any number of threads, random if not set
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<B256>,
+    /// Reuses the salt mined before for the same requirements and init code hash, and records new ones
+    #[arg(long, value_name = "CACHE_FILE")]
+    pub cache: Option<PathBuf>,
+    /// Keeps searching for the best address within the limits instead of stopping at the first match
+    #[arg(long, value_enum, value_name = "OBJECTIVE", conflicts_with = "cache")]
+    pub score: Option<ScoreObjective>,
+    /// Gives up with exit code 3 after this many seconds instead of searching forever
+    #[arg(long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+    /// Gives up with exit code 4 once this many salt indices are searched instead of searching forever
+    #[arg(long, value_name = "ATTEMPTS")]
+    pub max_attempts: Option<u64>,
+    /// Mines this many distinct salts per job, the lowest matching ones after the seed
+    #[arg(
+        long,
+        value_name = "COUNT",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with_all = ["cache", "score"]
+    )]
+    pub count: Option<u32>,
+    /// A JSON array of launches overriding the msg sender, token and config data of the command line
+    #[arg(long, value_name = "MANIFEST_FILE", conflicts_with_all = ["cache", "score"])]
+    pub manifest: Option<PathBuf>,
+    /// Only searches every n-th block of salts starting at block i, so n machines can split a search with the same seed
+    #[arg(
+        long,
+        value_name = "i/n",
+        value_parser = Shard::from_str,
+        requires = "seed",
+        conflicts_with_all = ["cache", "score", "count", "manifest"]
+    )]
+    pub shard: Option<Shard>,
+    /// Records the progress of the search to resume it from there if interrupted
+    #[arg(long, value_name = "CHECKPOINT_FILE", conflicts_with_all = ["score", "count", "manifest"])]
+    pub checkpoint: Option<PathBuf>,
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+    /// How the results are printed
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    #[command(flatten)]
+    pub init_code: InitCodeArgs,
+    #[command(flatten)]
+    pub requirements: RequirementArgs,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Run properties and results as text, only the salt with `--quiet`
+    Text,
+    /// Results as a single JSON object
+    Json,
+    /// Hex of `abi.encode(bytes32 salt, address predicted, bytes32 create2Salt)`, or with `--count` or `--manifest`
+    /// of `abi.encode(bytes32[] salts, address[] predicted, bytes32[] create2Salts)`, or of the `MigrationData` of a
+    /// simulated migration, for `vm.ffi`
+    Abi,
+}
+
+#[derive(Args)]
+pub struct VerifyArgs {
+    #[arg(long, value_name = "SALT")]
+    pub salt: B256,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+    #[command(flatten)]
+    pub init_code: InitCodeArgs,
+    #[command(flatten)]
+    pub requirements: RequirementArgs,
+}
+
+#[derive(Args)]
+pub struct PredictArgs {
+    #[arg(long, value_name = "SALT")]
+    pub salt: B256,
+    #[command(flatten)]
+    pub deployment: DeploymentArgs,
+    #[arg(short = 'q', long)]
+    pub quiet: bool,
+    #[arg(short = 'k', long, value_enum)]
+    pub strategy_kind: Option<StrategyKind>,
+    #[command(flatten)]
+    pub init_code: InitCodeArgs,
+}
+
+#[derive(Args)]
+pub struct SimulateMigrationArgs {
+    #[arg(short = 'k', long, value_enum)]
+    pub strategy_kind: StrategyKind,
+    /// The token of the pool, for a virtual token its underlying token
+    #[arg(long, value_name = "TOKEN_ADDRESS")]
+    pub token: Address,
+    /// The total supply of the token, the amount the strategy is created with
+    #[arg(long, value_name = "TOTAL_SUPPLY")]
+    pub total_supply: U256,
+    /// The config data passed to the strategy factory, its migrator parameters are simulated
+    #[arg(long, value_name = "CONFIG_DATA", value_parser = Bytes::from_str)]
+    pub config_data: Bytes,
+    /// The address of the strategy, the hooks of the pool and the recipient of the take pair.
+    /// Required to print the plan passed to `modifyLiquidities`
+    #[arg(long, value_name = "STRATEGY_ADDRESS")]
+    pub strategy: Option<Address>,
+    #[command(flatten)]
+    pub overrides: ConfigOverrideArgs,
+    #[command(flatten)]
+    pub outcome: AuctionOutcomeArgs,
+    /// How the migration is printed
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+// Values replacing the ones of the config data, to try other values without encoding it again
+#[derive(Args)]
+pub struct ConfigOverrideArgs {
+    /// The currency raised by the auction, the zero address for ETH
+    #[arg(long, value_name = "CURRENCY_ADDRESS", help_heading = "Config data overrides")]
+    pub currency: Option<Address>,
+    // The ranges are the ones of the Solidity types, the values the strategy rejects are reported as its reverts
+    /// In hundredths of a bip, 1e6 = 100%
+    #[arg(
+        long,
+        value_name = "FEE",
+        value_parser = clap::value_parser!(u32).range(..1 << 24),
+        help_heading = "Config data overrides"
+    )]
+    pub pool_lp_fee: Option<u32>,
+    /// Between 1 and 32767
+    #[arg(
+        long,
+        value_name = "TICK_SPACING",
+        value_parser = clap::value_parser!(i32).range(-(1 << 23)..1 << 23),
+        allow_negative_numbers = true,
+        help_heading = "Config data overrides"
+    )]
+    pub pool_tick_spacing: Option<i32>,
+    /// The share of the reserved tokens going into the pool, in mps, 1e7 = 100%
+    #[arg(
+        long,
+        value_name = "MPS",
+        value_parser = clap::value_parser!(u32).range(..1 << 24),
+        help_heading = "Config data overrides"
+    )]
+    pub token_split: Option<u32>,
+    /// The most of the currency raised going into the positions of the pool
+    #[arg(long, value_name = "AMOUNT", help_heading = "Config data overrides")]
+    pub max_currency_amount_for_lp: Option<u128>,
+    /// Only in the config data of the advanced strategy
+    #[arg(long, value_name = "BOOL", help_heading = "Config data overrides")]
+    pub create_one_sided_token_position: Option<bool>,
+    /// Only in the config data of the advanced strategy
+    #[arg(long, value_name = "BOOL", help_heading = "Config data overrides")]
+    pub create_one_sided_currency_position: Option<bool>,
+}
+
+// The LBPInitializationParams the initializer reports once the auction has ended
+#[derive(Args)]
+pub struct AuctionOutcomeArgs {
+    /// The clearing price of the auction, currency per token as a Q96 fixed point number
+    #[arg(long, value_name = "PRICE_X96", help_heading = "Auction outcome")]
+    pub initial_price_x96: U256,
+    /// Not used by the migration
+    #[arg(long, value_name = "AMOUNT", default_value_t = U256::ZERO, help_heading = "Auction outcome")]
+    pub tokens_sold: U256,
+    /// The currency the strategy received from the auction
+    #[arg(long, value_name = "AMOUNT", help_heading = "Auction outcome")]
+    pub currency_raised: U256,
+}
+
+// The strategy factory and the addresses the salt is hashed with on its way to it.
+// By default the salt goes through the token launcher, `--direct-factory` skips it and
+// `--wrapping-senders` replaces both with a custom chain.
+#[derive(Args, Clone)]
+pub struct DeploymentArgs {
+    #[arg(short, long, value_name = "MSG_SENDER")]
+    pub msg_sender: Option<Address>,
+    #[arg(short, long, value_name = "STRATEGY_FACTORY_ADDRESS")]
+    pub strategy_address: Option<Address>,
+    #[arg(short = 'l', long, value_name = "TOKEN_LAUNCHER_ADDRESS", conflicts_with = "direct_factory")]
+    pub token_launcher_address: Option<Address>,
+    #[arg(long)]
+    pub direct_factory: bool,
+    #[arg(
+        long,
+        value_name = "SENDERS",
+        value_delimiter = ',',
+        conflicts_with_all = ["msg_sender", "token_launcher_address", "direct_factory"]
+    )]
+    pub wrapping_senders: Vec<Address>,
+}
+
+// What the strategy address must fulfill
+#[derive(Args)]
+pub struct RequirementArgs {
+    #[arg(short = 'p', long, value_name = "VANITY_PREFIX")]
+    pub vanity_prefix: Option<String>,
+    #[arg(long, value_name = "VANITY_SUFFIX")]
+    pub vanity_suffix: Option<String>,
+    /// Hex characters and `?` wildcards from the left of the address, e.g. 0xdead????beef
+    #[arg(long, value_name = "VANITY_MASK")]
+    pub vanity_mask: Option<String>,
+    /// Hex characters the address must contain anywhere, can be repeated
+    #[arg(long, value_name = "HEX")]
+    pub vanity_contains: Vec<String>,
+    /// Matched against the EIP-55 checksummed address without 0x
+    #[arg(long, value_name = "REGEX")]
+    pub vanity_regex: Option<String>,
+    #[arg(short = 'c', long)]
+    pub case_sensitive: bool,
+    #[arg(short = 'k', long, value_enum, conflicts_with = "HookPermissions")]
+    pub strategy_kind: Option<StrategyKind>,
+    #[command(flatten)]
+    pub hook_permissions: HookPermissions,
+}
+
+// Either the init code hash or the parameters to compute it from the forge artifacts
+#[derive(Args, Clone)]
+pub struct InitCodeArgs {
+    pub init_code_hash: Option<B256>,
+    #[arg(long, value_name = "OUT_DIR", default_value = "out", help_heading = "Init code")]
+    pub artifacts: PathBuf,
+    #[arg(long, value_name = "TOKEN_ADDRESS", conflicts_with = "init_code_hash", help_heading = "Init code")]
+    pub token: Option<Address>,
+    #[arg(long, value_name = "TOTAL_SUPPLY", conflicts_with = "init_code_hash", help_heading = "Init code")]
+    pub total_supply: Option<U256>,
+    // `Bytes` implements `From<String>`, which clap would prefer over parsing the hex string
+    #[arg(
+        long,
+        value_name = "CONFIG_DATA",
+        value_parser = Bytes::from_str,
+        conflicts_with = "init_code_hash",
+        help_heading = "Init code"
+    )]
+    pub config_data: Option<Bytes>,
+    #[arg(
+        long,
+        value_name = "POSITION_MANAGER_ADDRESS",
+        conflicts_with = "init_code_hash",
+        help_heading = "Init code"
+    )]
+    pub position_manager: Option<Address>,
+    #[arg(long, value_name = "POOL_MANAGER_ADDRESS", conflicts_with = "init_code_hash", help_heading = "Init code")]
+    pub pool_manager: Option<Address>,
+}
+
+impl DeploymentArgs {
+    /// Returns the strategy factory address and the salt derivation, exiting if any address is missing
+    pub fn resolve(&self) -> (Address, SaltDerivation) {
+        let strategy_address = self.strategy_address.unwrap_or_default();
+        if strategy_address == Address::ZERO {
+            exit_with_error("Invalid strategy address");
+        }
+
+        if !self.wrapping_senders.is_empty() {
+            if self.wrapping_senders.contains(&Address::ZERO) {
+                exit_with_error("Invalid wrapping sender address");
+            }
+            return (strategy_address, SaltDerivation::new(self.wrapping_senders.clone()));
+        }
+
+        let msg_sender_address = self.msg_sender.unwrap_or_default();
+        if msg_sender_address == Address::ZERO {
+            exit_with_error("Invalid msg_sender address");
+        }
+        if self.direct_factory {
+            return (strategy_address, SaltDerivation::direct_factory(msg_sender_address));
+        }
+
+        let token_launcher_address = self.token_launcher_address.unwrap_or_default();
+        if token_launcher_address == Address::ZERO {
+            exit_with_error("Invalid token launcher address");
+        }
+        (strategy_address, SaltDerivation::via_token_launcher(msg_sender_address, token_launcher_address))
+    }
+
+    /// Same as `resolve` with the msg sender of a manifest job, if set, instead of `--msg-sender`
+    pub fn resolve_job(&self, job: &ManifestJob) -> (Address, SaltDerivation) {
+        if job.msg_sender.is_some() && !self.wrapping_senders.is_empty() {
+            exit_with_error("Manifest msg senders cannot be combined with wrapping senders");
+        }
+        Self { msg_sender: job.msg_sender.or(self.msg_sender), ..self.clone() }.resolve()
+    }
+}
+
+impl MineArgs {
+    /// Returns the limits of the search
+    pub fn limits(&self) -> SearchLimits {
+        SearchLimits { max_attempts: self.max_attempts, timeout: self.timeout.map(Duration::from_secs) }
+    }
+
+    /// Returns true if several salts or the salts of a manifest are mined at once
+    pub fn is_batch(&self) -> bool {
+        self.count.is_some() || self.manifest.is_some()
+    }
+
+    /// Returns the jobs of the manifest, or a single job taking everything from the command line without one,
+    /// exiting if the manifest cannot be loaded
+    pub fn jobs(&self) -> Vec<ManifestJob> {
+        match &self.manifest {
+            Some(path) => load_manifest(path).unwrap_or_else(|err| exit_with_error(err)),
+            None => vec![ManifestJob::default()],
+        }
+    }
+}
+
+impl RequirementArgs {
+    /// Returns the hook permissions of the strategy kind or the explicitly set ones,
+    /// exiting if none are set or no hook address with them would be valid
+    pub fn hook_permissions(&self) -> HookPermissions {
+        let hook_permissions = match self.strategy_kind {
+            Some(strategy_kind) => strategy_kind.hook_permissions(),
+            None => self.hook_permissions,
+        };
+        if hook_permissions.is_empty() {
+            exit_with_error("No hook permissions set");
+        }
+        if let Err(err) = hook_permissions.validate(STRATEGY_POOL_FEE) {
+            exit_with_error(format!("Invalid hook permissions: {}", err));
+        }
+        hook_permissions
+    }
+
+    /// Returns the vanity requirements, exiting if a pattern is invalid or cannot be fulfilled together with the
+    /// hook permissions
+    pub fn vanity(&self, hook_permissions: HookPermissions) -> Vanity {
+        let mut vanity = Ok(Vanity::default().case_sensitive(self.case_sensitive));
+        if let Some(prefix) = &self.vanity_prefix {
+            vanity = vanity.and_then(|vanity| vanity.with_prefix(prefix));
+        }
+        if let Some(suffix) = &self.vanity_suffix {
+            vanity = vanity.and_then(|vanity| vanity.with_suffix(suffix));
+        }
+        if let Some(mask) = &self.vanity_mask {
+            vanity = vanity.and_then(|vanity| vanity.with_mask(mask));
+        }
+        for text in &self.vanity_contains {
+            vanity = vanity.and_then(|vanity| vanity.with_contains(text));
+        }
+        if let Some(regex) = &self.vanity_regex {
+            vanity = vanity.and_then(|vanity| vanity.with_regex(regex));
+        }
+        let vanity = vanity.unwrap_or_else(|err| exit_with_error(format!("Invalid vanity: {}", err)));
+        if let Err(err) = vanity.check_hook_flags(hook_permissions.flags()) {
+            exit_with_error(format!("Invalid vanity: {}", err));
+        }
+        vanity
+    }
+}
+
+impl SimulateMigrationArgs {
+    /// Returns the total supply, exiting if it does not fit in 128 bits like the strategy factories do
+    pub fn total_supply(&self) -> u128 {
+        if self.total_supply > U256::from(u128::MAX) {
+            exit_with_error(StrategyError::InvalidAmount(self.total_supply, u128::MAX));
+        }
+        self.total_supply.to::<u128>()
+    }
+
+    /// Returns the migrator parameters of the config data with the overrides applied,
+    /// exiting if the config data does not decode to the layout of the strategy kind
+    pub fn migrator_params(&self) -> MigratorParameters {
+        let mut params =
+            self.strategy_kind.migrator_params(&self.config_data).unwrap_or_else(|err| exit_with_error(err));
+        let overrides = &self.overrides;
+        if let Some(currency) = overrides.currency {
+            params.currency = currency;
+        }
+        if let Some(pool_lp_fee) = overrides.pool_lp_fee {
+            params.poolLPFee = U24::from(pool_lp_fee);
+        }
+        if let Some(pool_tick_spacing) = overrides.pool_tick_spacing {
+            params.poolTickSpacing = I24::unchecked_from(pool_tick_spacing);
+        }
+        if let Some(token_split) = overrides.token_split {
+            params.tokenSplit = U24::from(token_split);
+        }
+        if let Some(max_currency_amount_for_lp) = overrides.max_currency_amount_for_lp {
+            params.maxCurrencyAmountForLP = max_currency_amount_for_lp;
+        }
+        params
+    }
+
+    /// Returns whether the advanced strategy creates the one-sided token and currency positions, with the overrides
+    /// applied, or `None` for the strategies without them. Exits if the overrides are set for one of those.
+    pub fn one_sided_positions(&self) -> Option<(bool, bool)> {
+        let overrides = &self.overrides;
+        if self.strategy_kind != StrategyKind::Advanced {
+            if overrides.create_one_sided_token_position.is_some()
+                || overrides.create_one_sided_currency_position.is_some()
+            {
+                exit_with_error(format!("{} does not create one-sided positions", self.strategy_kind));
+            }
+            return None;
+        }
+        let config = AdvancedConfigData::abi_decode_config(&self.config_data)
+            .unwrap_or_else(|err| exit_with_error(StrategyError::from(err)));
+        Some((
+            overrides.create_one_sided_token_position.unwrap_or(config.createOneSidedTokenPosition),
+            overrides.create_one_sided_currency_position.unwrap_or(config.createOneSidedCurrencyPosition),
+        ))
+    }
+}
+
+impl AuctionOutcomeArgs {
+    pub fn lbp_params(&self) -> LBPInitializationParams {
+        LBPInitializationParams {
+            initialPriceX96: self.initial_price_x96,
+            tokensSold: self.tokens_sold,
+            currencyRaised: self.currency_raised,
+        }
+    }
+}
+
+impl InitCodeArgs {
+    /// Returns the given init code hash, or computes it from the forge artifacts of the strategy
+    pub fn resolve(&self, strategy_kind: Option<StrategyKind>) -> B256 {
+        let init_code_hash = match (self.init_code_hash, strategy_kind) {
+            (Some(init_code_hash), _) => init_code_hash,
+            (None, Some(strategy_kind)) => self.init_code_hash_from_artifacts(strategy_kind),
+            (None, None) => B256::ZERO,
+        };
+        if init_code_hash == B256::ZERO {
+            exit_with_error("Invalid initialization code hash");
+        }
+        init_code_hash
+    }
+
+    /// Returns the launch parameters the init code hash is computed from, none if it is given
+    pub fn launch_inputs(&self, strategy_kind: Option<StrategyKind>) -> LaunchInputs {
+        if self.init_code_hash.is_some() {
+            return LaunchInputs::default();
+        }
+        LaunchInputs::new(strategy_kind, self.token, self.total_supply, self.config_data.as_ref())
+    }
+
+    /// Same as `resolve` with the token and config data of a manifest job, if set, instead of the command line ones
+    pub fn resolve_job(&self, strategy_kind: Option<StrategyKind>, job: &ManifestJob) -> B256 {
+        if (job.token.is_some() || job.config_data.is_some()) && self.init_code_hash.is_some() {
+            exit_with_error("Manifest tokens and config data require the init code hash to be computed from the artifacts");
+        }
+        let config_data = job.config_data.clone().or_else(|| self.config_data.clone());
+        Self { token: job.token.or(self.token), config_data, ..self.clone() }.resolve(strategy_kind)
+    }
+
+    /// Computes the init code hash of the strategy from the forge artifacts and the launch parameters
+    fn init_code_hash_from_artifacts(&self, strategy_kind: StrategyKind) -> B256 {
+        let (Some(token), Some(total_supply), Some(config_data), Some(position_manager), Some(pool_manager)) = (
+            self.token,
+            self.total_supply,
+            self.config_data.clone(),
+            self.position_manager,
+            self.pool_manager,
+        ) else {
+            exit_with_error(
+                "Either an init code hash or the token, total supply, config data, position manager and pool manager are required",
+            );
+        };
+        let params = LaunchParams { token, total_supply, config_data, position_manager, pool_manager };
+
+        let creation_code = load_creation_code(&self.artifacts, strategy_kind.contract_name())
+            .unwrap_or_else(|err| exit_with_error(err));
+        strategy_kind.init_code_hash(&creation_code, &params).unwrap_or_else(|err| exit_with_error(err))
+    }
+}
+
+/// Exit code of invalid arguments and any failure without a dedicated code, clap exits with 2 on usage errors
+pub const EXIT_ERROR: i32 = 1;
+/// Exit code of a search reaching `--timeout` without finding a salt
+pub const EXIT_TIMEOUT: i32 = 3;
+/// Exit code of a search reaching `--max-attempts` without finding a salt
+pub const EXIT_MAX_ATTEMPTS: i32 = 4;
+
+/// Prints the error and exits with a non-zero code
+pub fn exit_with_error(err: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", err);
+    std::process::exit(EXIT_ERROR);
+}
+
+/// Returns the exit code of a search reaching a limit
+pub fn search_exit_code(err: &SearchError) -> i32 {
+    match err {
+        SearchError::Timeout { .. } => EXIT_TIMEOUT,
+        SearchError::MaxAttempts { .. } => EXIT_MAX_ATTEMPTS,
+    }
+}
@@ -0,0 +1,65 @@
+use crate::abi_encode_sender_and_salt;
+use alloy_primitives::{Address, B256};
+use std::fmt;
+
+/// The salt `LBPStrategyBase.onTokensReceived` passes to the initializer factory
+pub const NESTED_INITIALIZER_SALT: B256 = B256::ZERO;
+
+/// The senders a salt is successively hashed with as `keccak256(abi.encode(sender, salt))` before the
+/// strategy factory uses it for CREATE2, innermost first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SaltDerivation {
+    senders: Vec<Address>,
+}
+
+impl SaltDerivation {
+    /// A custom chain of wrapping senders, innermost first
+    pub fn new(senders: Vec<Address>) -> Self {
+        Self { senders }
+    }
+
+    /// A launch through `LiquidityLauncher.distributeToken`, also when batched via multicall. The token launcher
+    /// hashes the salt with the msg sender, then the strategy factory hashes it with the token launcher.
+    pub fn via_token_launcher(msg_sender_address: Address, token_launcher_address: Address) -> Self {
+        Self::new(vec![msg_sender_address, token_launcher_address])
+    }
+
+    /// A direct call to `StrategyFactory.initializeDistribution`, which hashes the salt with the msg sender only
+    pub fn direct_factory(msg_sender_address: Address) -> Self {
+        Self::new(vec![msg_sender_address])
+    }
+
+    /// The initializer deployed by `LBPStrategyBase.onTokensReceived`, which calls the initializer factory itself.
+    /// The salt is always `NESTED_INITIALIZER_SALT`, so its address can be predicted but not mined.
+    pub fn nested_initializer(strategy_address: Address) -> Self {
+        Self::direct_factory(strategy_address)
+    }
+
+    /// Returns the wrapping senders, innermost first
+    pub fn senders(&self) -> &[Address] {
+        &self.senders
+    }
+
+    /// Returns the salt after each wrapping sender, the last one being the CREATE2 salt
+    pub fn intermediate_salts(&self, salt: B256) -> Vec<B256> {
+        self.senders
+            .iter()
+            .scan(salt, |salt, sender| {
+                *salt = abi_encode_sender_and_salt(*sender, *salt);
+                Some(*salt)
+            })
+            .collect()
+    }
+
+    /// Returns the salt the strategy factory uses for CREATE2
+    pub fn derive(&self, salt: B256) -> B256 {
+        self.senders.iter().fold(salt, |salt, sender| abi_encode_sender_and_salt(*sender, salt))
+    }
+}
+
+impl fmt::Display for SaltDerivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let senders: Vec<String> = self.senders.iter().map(|sender| format!("{:?}", sender)).collect();
+        write!(f, "{}", senders.join(" -> "))
+    }
+}
@@ -0,0 +1,303 @@
+use alloy_primitives::Address;
+use clap::Args;
+use std::fmt;
+
+// Flag bits as defined in v4-core Hooks.sol
+pub const BEFORE_INITIALIZE_FLAG: u16 = 1 << 13;
+pub const AFTER_INITIALIZE_FLAG: u16 = 1 << 12;
+pub const BEFORE_ADD_LIQUIDITY_FLAG: u16 = 1 << 11;
+pub const AFTER_ADD_LIQUIDITY_FLAG: u16 = 1 << 10;
+pub const BEFORE_REMOVE_LIQUIDITY_FLAG: u16 = 1 << 9;
+pub const AFTER_REMOVE_LIQUIDITY_FLAG: u16 = 1 << 8;
+pub const BEFORE_SWAP_FLAG: u16 = 1 << 7;
+pub const AFTER_SWAP_FLAG: u16 = 1 << 6;
+pub const BEFORE_DONATE_FLAG: u16 = 1 << 5;
+pub const AFTER_DONATE_FLAG: u16 = 1 << 4;
+pub const BEFORE_SWAP_RETURNS_DELTA_FLAG: u16 = 1 << 3;
+pub const AFTER_SWAP_RETURNS_DELTA_FLAG: u16 = 1 << 2;
+pub const AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG: u16 = 1 << 1;
+pub const AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG: u16 = 1 << 0;
+
+/// The name of each flag in Hooks.Permissions, from the most significant bit down
+const FLAG_NAMES: [(u16, &str); 14] = [
+    (BEFORE_INITIALIZE_FLAG, "beforeInitialize"),
+    (AFTER_INITIALIZE_FLAG, "afterInitialize"),
+    (BEFORE_ADD_LIQUIDITY_FLAG, "beforeAddLiquidity"),
+    (AFTER_ADD_LIQUIDITY_FLAG, "afterAddLiquidity"),
+    (BEFORE_REMOVE_LIQUIDITY_FLAG, "beforeRemoveLiquidity"),
+    (AFTER_REMOVE_LIQUIDITY_FLAG, "afterRemoveLiquidity"),
+    (BEFORE_SWAP_FLAG, "beforeSwap"),
+    (AFTER_SWAP_FLAG, "afterSwap"),
+    (BEFORE_DONATE_FLAG, "beforeDonate"),
+    (AFTER_DONATE_FLAG, "afterDonate"),
+    (BEFORE_SWAP_RETURNS_DELTA_FLAG, "beforeSwapReturnDelta"),
+    (AFTER_SWAP_RETURNS_DELTA_FLAG, "afterSwapReturnDelta"),
+    (AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG, "afterAddLiquidityReturnDelta"),
+    (AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG, "afterRemoveLiquidityReturnDelta"),
+];
+
+/// Mask of all the hook permission bits in the low 14 bits of a hook address
+pub const ALL_HOOK_MASK: u16 = (1 << 14) - 1;
+
+/// Mirrors v4-core LPFeeLibrary.DYNAMIC_FEE_FLAG
+pub const DYNAMIC_FEE_FLAG: u32 = 0x800000;
+
+/// Each return delta flag and the flag it requires, as checked by v4-core Hooks.isValidHookAddress
+const RETURN_DELTA_FLAGS: [(u16, u16); 4] = [
+    (BEFORE_SWAP_RETURNS_DELTA_FLAG, BEFORE_SWAP_FLAG),
+    (AFTER_SWAP_RETURNS_DELTA_FLAG, AFTER_SWAP_FLAG),
+    (AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG, AFTER_ADD_LIQUIDITY_FLAG),
+    (AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG, AFTER_REMOVE_LIQUIDITY_FLAG),
+];
+
+/// Why `PoolManager.initialize` would revert with `HookAddressNotValid`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookAddressError {
+    /// A return delta flag is set without the flag of its action
+    ReturnDeltaWithoutAction { return_delta_flag: u16, action_flag: u16 },
+    /// A hook without any flag set must have a dynamic fee
+    NoFlagsWithStaticFee,
+    /// A pool without a hook cannot have a dynamic fee
+    DynamicFeeWithoutHook,
+}
+
+impl fmt::Display for HookAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HookAddressError::ReturnDeltaWithoutAction { return_delta_flag, action_flag } => write!(
+                f,
+                "{} requires {}",
+                flag_name(*return_delta_flag),
+                flag_name(*action_flag)
+            ),
+            HookAddressError::NoFlagsWithStaticFee => write!(f, "A hook without permissions requires a dynamic fee"),
+            HookAddressError::DynamicFeeWithoutHook => write!(f, "A dynamic fee requires a hook"),
+        }
+    }
+}
+
+impl std::error::Error for HookAddressError {}
+
+/// Mirrors v4-core Hooks.Permissions
+#[derive(Args, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[command(about = None, long_about = None, next_help_heading = "Hook permissions")]
+pub struct HookPermissions {
+    #[arg(long)]
+    pub before_initialize: bool,
+    #[arg(long)]
+    pub after_initialize: bool,
+    #[arg(long)]
+    pub before_add_liquidity: bool,
+    #[arg(long)]
+    pub after_add_liquidity: bool,
+    #[arg(long)]
+    pub before_remove_liquidity: bool,
+    #[arg(long)]
+    pub after_remove_liquidity: bool,
+    #[arg(long)]
+    pub before_swap: bool,
+    #[arg(long)]
+    pub after_swap: bool,
+    #[arg(long)]
+    pub before_donate: bool,
+    #[arg(long)]
+    pub after_donate: bool,
+    #[arg(long)]
+    pub before_swap_return_delta: bool,
+    #[arg(long)]
+    pub after_swap_return_delta: bool,
+    #[arg(long)]
+    pub after_add_liquidity_return_delta: bool,
+    #[arg(long)]
+    pub after_remove_liquidity_return_delta: bool,
+}
+
+impl HookPermissions {
+    /// Returns the flag word the hook address must have in its low 14 bits
+    pub const fn flags(&self) -> u16 {
+        let mut flags = 0;
+        if self.before_initialize {
+            flags |= BEFORE_INITIALIZE_FLAG;
+        }
+        if self.after_initialize {
+            flags |= AFTER_INITIALIZE_FLAG;
+        }
+        if self.before_add_liquidity {
+            flags |= BEFORE_ADD_LIQUIDITY_FLAG;
+        }
+        if self.after_add_liquidity {
+            flags |= AFTER_ADD_LIQUIDITY_FLAG;
+        }
+        if self.before_remove_liquidity {
+            flags |= BEFORE_REMOVE_LIQUIDITY_FLAG;
+        }
+        if self.after_remove_liquidity {
+            flags |= AFTER_REMOVE_LIQUIDITY_FLAG;
+        }
+        if self.before_swap {
+            flags |= BEFORE_SWAP_FLAG;
+        }
+        if self.after_swap {
+            flags |= AFTER_SWAP_FLAG;
+        }
+        if self.before_donate {
+            flags |= BEFORE_DONATE_FLAG;
+        }
+        if self.after_donate {
+            flags |= AFTER_DONATE_FLAG;
+        }
+        if self.before_swap_return_delta {
+            flags |= BEFORE_SWAP_RETURNS_DELTA_FLAG;
+        }
+        if self.after_swap_return_delta {
+            flags |= AFTER_SWAP_RETURNS_DELTA_FLAG;
+        }
+        if self.after_add_liquidity_return_delta {
+            flags |= AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG;
+        }
+        if self.after_remove_liquidity_return_delta {
+            flags |= AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG;
+        }
+        flags
+    }
+
+    /// Builds the permissions encoded in a flag word, ignoring bits outside of the hook mask
+    pub const fn from_flags(flags: u16) -> Self {
+        Self {
+            before_initialize: flags & BEFORE_INITIALIZE_FLAG != 0,
+            after_initialize: flags & AFTER_INITIALIZE_FLAG != 0,
+            before_add_liquidity: flags & BEFORE_ADD_LIQUIDITY_FLAG != 0,
+            after_add_liquidity: flags & AFTER_ADD_LIQUIDITY_FLAG != 0,
+            before_remove_liquidity: flags & BEFORE_REMOVE_LIQUIDITY_FLAG != 0,
+            after_remove_liquidity: flags & AFTER_REMOVE_LIQUIDITY_FLAG != 0,
+            before_swap: flags & BEFORE_SWAP_FLAG != 0,
+            after_swap: flags & AFTER_SWAP_FLAG != 0,
+            before_donate: flags & BEFORE_DONATE_FLAG != 0,
+            after_donate: flags & AFTER_DONATE_FLAG != 0,
+            before_swap_return_delta: flags & BEFORE_SWAP_RETURNS_DELTA_FLAG != 0,
+            after_swap_return_delta: flags & AFTER_SWAP_RETURNS_DELTA_FLAG != 0,
+            after_add_liquidity_return_delta: flags & AFTER_ADD_LIQUIDITY_RETURNS_DELTA_FLAG != 0,
+            after_remove_liquidity_return_delta: flags & AFTER_REMOVE_LIQUIDITY_RETURNS_DELTA_FLAG != 0,
+        }
+    }
+
+    /// Returns the permissions a hook deployed at the given address has
+    pub fn from_address(address: Address) -> Self {
+        Self::from_flags(hook_flags(address))
+    }
+
+    /// Returns true if no permission is set
+    pub const fn is_empty(&self) -> bool {
+        self.flags() == 0
+    }
+
+    /// Checks that a hook with exactly these permissions passes v4-core Hooks.isValidHookAddress for the fee
+    pub fn validate(&self, fee: u32) -> Result<(), HookAddressError> {
+        let flags = self.flags();
+        for (return_delta_flag, action_flag) in RETURN_DELTA_FLAGS {
+            if flags & return_delta_flag != 0 && flags & action_flag == 0 {
+                return Err(HookAddressError::ReturnDeltaWithoutAction { return_delta_flag, action_flag });
+            }
+        }
+        if flags == 0 && !is_dynamic_fee(fee) {
+            return Err(HookAddressError::NoFlagsWithStaticFee);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for HookPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = self.flags();
+        let names: Vec<&str> = FLAG_NAMES
+            .iter()
+            .filter(|(flag, _)| flags & flag != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        write!(f, "{:#06x} ({})", flags, names.join(", "))
+    }
+}
+
+/// Returns the hook permission bits of an address
+pub fn hook_flags(address: Address) -> u16 {
+    u16::from_be_bytes([address[18], address[19]]) & ALL_HOOK_MASK
+}
+
+/// Mirrors v4-core LPFeeLibrary.isDynamicFee
+pub const fn is_dynamic_fee(fee: u32) -> bool {
+    fee == DYNAMIC_FEE_FLAG
+}
+
+/// Mirrors v4-core Hooks.isValidHookAddress, returning the rule the address breaks for a pool with the fee
+pub fn validate_hook_address(address: Address, fee: u32) -> Result<(), HookAddressError> {
+    if address == Address::ZERO {
+        if is_dynamic_fee(fee) {
+            return Err(HookAddressError::DynamicFeeWithoutHook);
+        }
+        return Ok(());
+    }
+    HookPermissions::from_address(address).validate(fee)
+}
+
+/// Returns true if `PoolManager.initialize` accepts the address as the hook of a pool with the fee
+pub fn is_valid_hook_address(address: Address, fee: u32) -> bool {
+    validate_hook_address(address, fee).is_ok()
+}
+
+fn flag_name(flag: u16) -> &'static str {
+    FLAG_NAMES.iter().find(|(f, _)| *f == flag).map(|(_, name)| *name).unwrap_or("unknown")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An address whose low 14 bits are the flags, with a nonzero high byte so it is never the zero address
+    fn hook_address(flags: u16) -> Address {
+        let mut bytes = [0u8; 20];
+        bytes[0] = 0xff;
+        bytes[18..].copy_from_slice(&flags.to_be_bytes());
+        Address::from(bytes)
+    }
+
+    #[test]
+    fn return_delta_flags_require_their_action_flag() {
+        for (return_delta_flag, action_flag) in RETURN_DELTA_FLAGS {
+            assert_eq!(
+                validate_hook_address(hook_address(return_delta_flag), 0),
+                Err(HookAddressError::ReturnDeltaWithoutAction { return_delta_flag, action_flag })
+            );
+            // Even with a dynamic fee, or along with another flag
+            assert!(!is_valid_hook_address(hook_address(return_delta_flag), DYNAMIC_FEE_FLAG));
+            assert!(!is_valid_hook_address(hook_address(return_delta_flag | BEFORE_INITIALIZE_FLAG), 0));
+            assert!(is_valid_hook_address(hook_address(return_delta_flag | action_flag), 0));
+        }
+    }
+
+    #[test]
+    fn hook_without_flags_requires_a_dynamic_fee() {
+        let address = hook_address(0);
+        assert_eq!(validate_hook_address(address, 3000), Err(HookAddressError::NoFlagsWithStaticFee));
+        assert_eq!(validate_hook_address(address, 0), Err(HookAddressError::NoFlagsWithStaticFee));
+        assert_eq!(validate_hook_address(address, DYNAMIC_FEE_FLAG), Ok(()));
+    }
+
+    #[test]
+    fn no_hook_cannot_have_a_dynamic_fee() {
+        assert_eq!(validate_hook_address(Address::ZERO, 3000), Ok(()));
+        assert_eq!(
+            validate_hook_address(Address::ZERO, DYNAMIC_FEE_FLAG),
+            Err(HookAddressError::DynamicFeeWithoutHook)
+        );
+    }
+
+    #[test]
+    fn flags_round_trip_through_addresses() {
+        let permissions = HookPermissions { before_initialize: true, before_swap: true, ..Default::default() };
+        assert_eq!(permissions.flags(), BEFORE_INITIALIZE_FLAG | BEFORE_SWAP_FLAG);
+        assert_eq!(HookPermissions::from_address(hook_address(permissions.flags())), permissions);
+        // Bits above the low 14 are not permissions
+        assert_eq!(hook_flags(hook_address(0xc000 | BEFORE_SWAP_FLAG)), BEFORE_SWAP_FLAG);
+        assert_eq!(HookPermissions::from_flags(ALL_HOOK_MASK).flags(), ALL_HOOK_MASK);
+    }
+}
@@ -1,5 +1,31 @@
-use alloy_primitives::{address, Address, B256, keccak256};
-use rand::Rng;
+use alloy_primitives::{Address, B256, keccak256};
+
+pub mod artifacts;
+pub mod cache;
+pub mod checkpoint;
+pub mod derivation;
+pub mod hooks;
+pub mod manifest;
+pub mod migration;
+pub mod math;
+pub mod plan;
+pub mod miner;
+pub mod positions;
+pub mod pricing;
+pub mod progress;
+pub mod score;
+pub mod strategy;
+pub mod ticks;
+pub mod types;
+pub mod vanity;
+
+use derivation::SaltDerivation;
+use hooks::{hook_flags, is_valid_hook_address, validate_hook_address, HookAddressError, HookPermissions};
+use miner::SaltMiner;
+use vanity::Vanity;
+
+/// The fee hook addresses are validated for, the strategies initialize their pool with a static `poolLPFee`
+pub const STRATEGY_POOL_FEE: u32 = 0;
 
 // Equivalent to Solidity abi.encode(address, bytes32)
 pub fn abi_encode_sender_and_salt(sender: Address, salt: B256) -> B256 {
@@ -10,35 +36,96 @@ pub fn abi_encode_sender_and_salt(sender: Address, salt: B256) -> B256 {
     keccak256(encoded)
 }
 
-/// Mine a salt that will result in a contract address with the desired hook permissions mask.
+/// Computes the address the strategy factory deploys to for a salt going through the given derivation
+pub fn compute_strategy_address(
+    strategy_address: Address,
+    init_code_hash: B256,
+    derivation: &SaltDerivation,
+    salt: B256,
+) -> Address {
+    strategy_address.create2(derivation.derive(salt), init_code_hash)
+}
+
+/// Checks if an address has exactly the desired hook permissions, is accepted by `PoolManager.initialize`
+/// and fulfills the vanity requirements
+pub fn fulfills_requirements(address: Address, hook_permissions: HookPermissions, vanity: &Vanity) -> bool {
+    hook_flags(address) == hook_permissions.flags()
+        && is_valid_hook_address(address, STRATEGY_POOL_FEE)
+        && fulfills_vanity(address, vanity)
+}
+
+/// Returns the probability of a salt fulfilling the requirements, unknown if the vanity has a regex.
+/// The low 14 bits of an address must be exactly the hook permissions, which happens once in 2^14 addresses.
+pub fn success_probability(hook_permissions: HookPermissions, vanity: &Vanity) -> Option<f64> {
+    vanity.probability(hook_permissions.flags()).map(|probability| probability / (1u64 << 14) as f64)
+}
+
+/// Mine a salt that will result in a strategy address with the desired hook permissions and vanity.
+/// Searches from a random seed on a single thread, use `SaltMiner` to control both.
+/// Never returns if the hook permissions fail `HookPermissions::validate` or `Vanity::check_hook_flags`.
 pub fn mine_salt(
     strategy_address: Address,
     init_code_hash: B256,
-    hook_permissions_mask: Address,
-    msg_sender_address: Address,
-    token_launcher_address: Address,
+    hook_permissions: HookPermissions,
+    derivation: &SaltDerivation,
+    vanity: &Vanity,
 ) -> B256 {
-    let all_hook_mask: Address = address!("0x0000000000000000000000000000000000003fff");
-    loop {
-        let salt = B256::from_slice(&rand::thread_rng().gen::<[u8; 32]>());
-
-        // Fix this implementation to do proper abi encoding 
-        let salt_with_msg_sender = abi_encode_sender_and_salt(msg_sender_address, salt);
-        let salt_with_token_launcher = abi_encode_sender_and_salt(token_launcher_address, salt_with_msg_sender);
-        let address = strategy_address.create2(salt_with_token_launcher, init_code_hash);
-        if hook_permissions_mask == address & all_hook_mask {
-            return salt;
-        }
+    SaltMiner::new(strategy_address, init_code_hash, hook_permissions, derivation, vanity)
+        .mine(B256::from(rand::random::<[u8; 32]>()), 1)
+        .salt
+}
+
+/// The salts a strategy address is derived from and the requirements it fulfills
+#[derive(Clone, Debug)]
+pub struct SaltVerification {
+    pub salt: B256,
+    /// The salt after each wrapping sender of the derivation, the last one being the CREATE2 salt
+    pub intermediate_salts: Vec<B256>,
+    pub address: Address,
+    pub hook_permissions: HookPermissions,
+    pub required_hook_permissions: HookPermissions,
+    /// Why `PoolManager.initialize` would reject the address as a hook, if it would
+    pub hook_address_error: Option<HookAddressError>,
+    pub fulfills_vanity: bool,
+}
+
+impl SaltVerification {
+    /// Returns true if the address has exactly the required hook permissions
+    pub fn fulfills_hook_permissions(&self) -> bool {
+        self.hook_permissions == self.required_hook_permissions
+    }
+
+    /// Returns true if the salt fulfills all the requirements, same as `fulfills_requirements`
+    pub fn is_valid(&self) -> bool {
+        self.fulfills_hook_permissions() && self.hook_address_error.is_none() && self.fulfills_vanity
     }
 }
 
-/// Checks if an address fulfills vanity requirements
-pub fn fulfills_vanity(address: Address, prefix: &str, case_sensitive: bool) -> bool {
-    if !case_sensitive {
-        let address_str = format!("{:x}", address);
-        address_str.starts_with(&prefix.to_lowercase())
-    } else {
-        let address_str = &address.to_checksum(None)[2..];
-        address_str.starts_with(prefix)
+/// Derives the strategy address of a salt and checks it against the same requirements `mine_salt` uses
+pub fn verify_salt(
+    strategy_address: Address,
+    init_code_hash: B256,
+    hook_permissions: HookPermissions,
+    derivation: &SaltDerivation,
+    vanity: &Vanity,
+    salt: B256,
+) -> SaltVerification {
+    let intermediate_salts = derivation.intermediate_salts(salt);
+    let create2_salt = intermediate_salts.last().copied().unwrap_or(salt);
+    let address = strategy_address.create2(create2_salt, init_code_hash);
+
+    SaltVerification {
+        salt,
+        intermediate_salts,
+        address,
+        hook_permissions: HookPermissions::from_address(address),
+        required_hook_permissions: hook_permissions,
+        hook_address_error: validate_hook_address(address, STRATEGY_POOL_FEE).err(),
+        fulfills_vanity: fulfills_vanity(address, vanity),
     }
-}
\ No newline at end of file
+}
+
+/// Checks if an address fulfills vanity requirements
+pub fn fulfills_vanity(address: Address, vanity: &Vanity) -> bool {
+    vanity.matches(address)
+}
@@ -1,162 +1,680 @@
-use alloy_primitives::{Address, B256};
+use alloy_primitives::{hex, Address, B256};
+use alloy_sol_types::SolValue;
 use clap::Parser;
-use spinners::{Spinner, Spinners};
-use std::str::FromStr;
-use std::sync::{Arc, RwLock};
-use std::thread;
-use address_miner::{fulfills_vanity, mine_salt, abi_encode_sender_and_salt};
-
-#[derive(Parser)]
-#[command(about, long_about = None)]
-struct Cli {
-    init_code_hash: Option<String>,
-    hook_permissions_mask: Option<String>,
-    #[arg(short, long, value_name = "MSG_SENDER")]
-    msg_sender: Option<String>,
-    #[arg(short, long, value_name = "STRATEGY_FACTORY_ADDRESS")]
-    strategy_address: Option<String>,
-    #[arg(short = 'l', long, value_name = "TOKEN_LAUNCHER_ADDRESS")]
-    token_launcher_address: Option<String>,
-    #[arg(
-        short = 't',
-        long,
-        value_name = "NUMBER_OF_THREADS",
-        default_value_t = 8
-    )]
-    threads: i32,
-    #[arg(short = 'p', long, value_name = "VANITY_PREFIX")]
-    vanity_prefix: Option<String>,
-    #[arg(short = 'c', long)]
-    case_sensitive: bool,
-    #[arg(short = 'q', long)]
-    quiet: bool
+use serde::Serialize;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+use address_miner::{compute_strategy_address, success_probability, verify_salt};
+use address_miner::cache::{CacheKey, SaltCache};
+use address_miner::checkpoint::Checkpoint;
+use address_miner::derivation::SaltDerivation;
+use address_miner::migration::LBPStrategy;
+use address_miner::plan::{advanced_migration_plan, full_range_migration_plan};
+use address_miner::positions::{plan_advanced_positions, OneSidedPosition, Position};
+use address_miner::miner::{mine_batch, MinedSalt, SaltMiner, SearchError, SearchProgress};
+use address_miner::progress::Progress;
+
+mod cli;
+
+use cli::{
+    exit_with_error, search_exit_code, Cli, Command, MineArgs, OutputFormat, PredictArgs, SimulateMigrationArgs,
+    VerifyArgs, EXIT_ERROR,
+};
+
+/// Result of a mine printed with `--format json`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MineOutput {
+    salt: B256,
+    salt_with_msg_sender: B256,
+    create2_salt: B256,
+    address: String,
+    hook_flags: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u32>,
+    cached: bool,
+    attempts: u64,
+    elapsed_seconds: f64,
+    hashrate: f64,
 }
 
-fn main() {
-    let cli = Cli::parse();
-    let mut msg_sender_address: Address = Address::ZERO;
-    let mut strategy_address: Address = Address::ZERO;
-    let mut token_launcher_address: Address = Address::ZERO;
-    let mut init_code_hash: B256 = B256::ZERO;
-    let mut hook_permissions_mask: Address = Address::ZERO;
-    let threads = cli.threads;
-    let case_sensitive = cli.case_sensitive;
-    let quiet = cli.quiet;
+/// A salt mined with `--count` or `--manifest`, printed with `--format json`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchSaltOutput {
+    /// Index of the job in the manifest, 0 without one
+    job: usize,
+    salt: B256,
+    salt_with_msg_sender: B256,
+    create2_salt: B256,
+    address: String,
+}
 
-    // Parse the command line arguments
-    if let Some(_msg_sender) = cli.msg_sender.as_deref() {
-        msg_sender_address =
-            Address::from_str(_msg_sender).expect("Error: Invalid deployer address");
-    }
-    if let Some(_init_code_hash) = cli.init_code_hash.as_deref() {
-        init_code_hash = B256::from_str(_init_code_hash).expect("Error: Invalid init code hash");
+/// Result of a batch mine printed with `--format json`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchOutput {
+    salts: Vec<BatchSaltOutput>,
+    hook_flags: u16,
+    attempts: u64,
+    elapsed_seconds: f64,
+    hashrate: f64,
+}
+
+/// Result of `simulate-migration` printed with `--format json`, amounts as decimal strings as they exceed 2^53
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationOutput {
+    currency_is_currency0: bool,
+    reserve_token_amount: String,
+    sqrt_price_x96: String,
+    full_range_token_amount: String,
+    full_range_currency_amount: String,
+    leftover_currency: String,
+    liquidity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    positions: Option<PositionsOutput>,
+    /// The encoded plan passed to `modifyLiquidities`, with `--strategy`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    plan: Option<String>,
+}
+
+/// The positions of an advanced strategy migration printed with `--format json`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionsOutput {
+    full_range: PositionOutput,
+    one_sided_token: PositionOutput,
+    one_sided_currency: PositionOutput,
+    token_transfer_amount: String,
+    currency_transfer_amount: String,
+}
+
+/// A position printed with `--format json`, the reason it is skipped if it is not minted
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionOutput {
+    minted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lower_tick: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upper_tick: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    liquidity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skip_reason: Option<String>,
+}
+
+impl PositionOutput {
+    fn minted(position: &Position, amount: Option<u128>) -> Self {
+        Self {
+            minted: true,
+            lower_tick: Some(position.bounds.lower_tick),
+            upper_tick: Some(position.bounds.upper_tick),
+            liquidity: Some(position.liquidity.to_string()),
+            amount: amount.map(|amount| amount.to_string()),
+            skip_reason: None,
+        }
     }
-    if let Some(_strategy_address) = cli.strategy_address.as_deref() {
-        strategy_address =
-            Address::from_str(_strategy_address).expect("Error: Invalid strategy address");
+
+    fn one_sided(position: &OneSidedPosition) -> Self {
+        match position {
+            OneSidedPosition::Minted { position, amount } => Self::minted(position, Some(*amount)),
+            OneSidedPosition::Skipped(reason) => Self {
+                minted: false,
+                lower_tick: None,
+                upper_tick: None,
+                liquidity: None,
+                amount: None,
+                skip_reason: Some(reason.to_string()),
+            },
+        }
     }
-    if let Some(_token_launcher_address) = cli.token_launcher_address.as_deref() {
-        token_launcher_address =
-            Address::from_str(_token_launcher_address).expect("Error: Invalid token launcher address");
+}
+
+/// Error printed on stderr with `--format json` or `--format abi` when a search reaches a limit
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchErrorOutput {
+    error: &'static str,
+    message: String,
+    exit_code: i32,
+    attempts: u64,
+    elapsed_seconds: f64,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Mine(args)) => mine(args),
+        Some(Command::Predict(args)) => predict(args),
+        Some(Command::Verify(args)) => verify(args),
+        Some(Command::SimulateMigration(args)) => simulate_migration(args),
+        None => mine(cli.mine),
     }
-    if let Some(_miner_address) = cli.hook_permissions_mask.as_deref() {
-        hook_permissions_mask =
-            Address::from_str(_miner_address).expect("Error: Invalid hook permission mask");
+}
+
+fn mine(args: MineArgs) {
+    if args.is_batch() {
+        return mine_many(args);
     }
-    let vanity_prefix = cli.vanity_prefix.clone().unwrap_or_default();
+    let (strategy_address, derivation) = args.deployment.resolve();
+    let strategy_kind = args.requirements.strategy_kind;
+    let init_code_hash = args.init_code.resolve(strategy_kind);
+    let hook_permissions = args.requirements.hook_permissions();
+    let vanity = args.requirements.vanity(hook_permissions);
+    let threads = args.threads.get();
+    let shard = args.shard.unwrap_or_default();
+    let quiet = args.quiet || args.format != OutputFormat::Text;
+    let probability = success_probability(hook_permissions, &vanity);
+    let launch = args.init_code.launch_inputs(strategy_kind);
+    let cache_key = CacheKey::new(strategy_address, &derivation, hook_permissions, &vanity, args.seed, launch);
 
     // Validate the command line arguments
-    if msg_sender_address == Address::ZERO {
-        eprintln!("Error: Invalid msg_sender address");
-        std::process::exit(1);
-    }
-    if init_code_hash == B256::ZERO {
-        eprintln!("Error: Invalid initialization code hash");
-        std::process::exit(1);
-    }
-    if strategy_address == Address::ZERO {
-        eprintln!("Error: Invalid strategy address");
-        std::process::exit(1);
-    }
-    if token_launcher_address == Address::ZERO {
-        eprintln!("Error: Invalid token launcher address");
-        std::process::exit(1);
-    }
-    if hook_permissions_mask == Address::ZERO {
-        eprintln!("Error:: Invalid miner address");
-        std::process::exit(1);
-    }
-    if !vanity_prefix.is_empty() && usize::from_str_radix(&vanity_prefix, 16).is_err() {
-        eprintln!("Error:: Invalid hex prefix");
-        std::process::exit(1);
+    if args.score.is_some() && args.timeout.is_none() && args.max_attempts.is_none() {
+        exit_with_error("Scoring requires a timeout or a maximum number of attempts");
     }
 
+    // Resume from the checkpoint of an interrupted search of the same inputs, with its seed unless one is set
+    let checkpoint = args.checkpoint.as_ref().and_then(|checkpoint_path| {
+        let checkpoint = Checkpoint::load(checkpoint_path).unwrap_or_else(|err| exit_with_error(err))?;
+        if !checkpoint.resumes(&cache_key, init_code_hash, shard) {
+            exit_with_error(format!(
+                "Checkpoint {} is for other inputs, remove it to start a new search",
+                checkpoint_path.display()
+            ));
+        }
+        Some(checkpoint)
+    });
+    let seed = args
+        .seed
+        .or(checkpoint.as_ref().and_then(|checkpoint| checkpoint.key.seed))
+        .unwrap_or_else(|| B256::from(rand::random::<[u8; 32]>()));
+    let (first_block, previous_attempts) =
+        checkpoint.as_ref().map_or((0, 0), |checkpoint| (checkpoint.next_block, checkpoint.attempts));
+
     // Print run properties
     if !quiet {
         println!("Run properties:");
-        println!(" * Msg sender address: {:?}", &msg_sender_address);
+        println!(" * Salt derivation: {}", &derivation);
         println!(" * Init code hash: {:?}", &init_code_hash);
-        println!(" * Hook permissions mask: {:?}", &hook_permissions_mask);
+        if let Some(strategy_kind) = strategy_kind {
+            println!(" * Strategy kind: {}", strategy_kind);
+        }
+        println!(" * Hook permissions: {}", &hook_permissions);
         println!(" * Strategy address: {:?}", &strategy_address);
-        println!(" * Token launcher address: {:?}", &token_launcher_address);
-        if !vanity_prefix.is_empty() {
-            println!(" * Vanity prefix: {:?}", &vanity_prefix);
+        println!(" * Seed: {:?}", &seed);
+        if let Some(cache_path) = &args.cache {
+            println!(" * Salt cache: {}", cache_path.display());
+        }
+        if let Some(shard) = args.shard {
+            println!(" * Shard: {}", shard);
+        }
+        if let Some(checkpoint_path) = &args.checkpoint {
+            match &checkpoint {
+                Some(checkpoint) => println!(
+                    " * Checkpoint: {} (resuming from block {} after {} attempts)",
+                    checkpoint_path.display(),
+                    checkpoint.next_block,
+                    checkpoint.attempts
+                ),
+                None => println!(" * Checkpoint: {}", checkpoint_path.display()),
+            }
+        }
+        if !vanity.is_empty() {
+            println!(" * Vanity: {}", &vanity);
             println!(" * Number of threads: {}", threads);
         }
+        match probability {
+            Some(probability) => println!(" * Expected attempts: ~{:.0}", 1.0 / probability),
+            None => println!(" * Expected attempts: unknown with a regex"),
+        }
+        if let Some(objective) = args.score {
+            println!(" * Score: {}", objective);
+        }
+        if let Some(timeout) = args.timeout {
+            println!(" * Timeout: {}s", timeout);
+        }
+        if let Some(max_attempts) = args.max_attempts {
+            println!(" * Max attempts: {}", max_attempts);
+        }
         println!();
     }
 
-    // Start Mining
-    
-    let mut sp: Option<Spinner> = if !quiet {
-        Some(Spinner::new(Spinners::Aesthetic, "Mining...".into()))
-    } else {
-        None 
+    let mut miner = SaltMiner::new(
+        strategy_address,
+        init_code_hash,
+        hook_permissions,
+        &derivation,
+        &vanity,
+    );
+
+    // Look up the salt cache, recomputing the address in case the cache was edited or mined with other rules
+    let cache = args.cache.as_ref().map(|cache_path| {
+        SaltCache::load(cache_path).unwrap_or_else(|err| {
+            eprintln!("Warning: {}", err);
+            SaltCache::default()
+        })
+    });
+    let cached_salt = cache
+        .as_ref()
+        .and_then(|cache| cache.get(&cache_key, init_code_hash))
+        .filter(|salt| miner.fulfills(*salt));
+
+    // Report the progress on stderr, which `vm.ffi` does not read, unless explicitly quiet
+    let start = Instant::now();
+    let on_progress = |attempts: u64| {
+        if !args.quiet {
+            // A match only ends a search without scoring
+            let probability = probability.filter(|_| args.score.is_none());
+            print_progress(&Progress { attempts, elapsed: start.elapsed(), probability });
+        }
     };
-    let shared_salt = Arc::new(RwLock::new(B256::ZERO));
-    let mut handles = vec![];
-    for _ in 0..threads {
-        let shared_salt_clone = Arc::clone(&shared_salt);
-        let vanity_prefix_clone = vanity_prefix.clone();
-
-        let handle = thread::spawn(move || {
-            while shared_salt_clone.read().unwrap().is_zero() {
-                let salt = mine_salt(strategy_address, init_code_hash, hook_permissions_mask, msg_sender_address, token_launcher_address);
-
-                // Note this is very much wrong for now - there is no vanity support
-                let address = msg_sender_address.create2(salt, init_code_hash);
-                if fulfills_vanity(address, &vanity_prefix_clone, case_sensitive) {
-                    *shared_salt_clone.write().unwrap() = salt;
-                }   
+    let (salt, attempts) = match cached_salt {
+        Some(salt) => (salt, 0),
+        None if args.score.is_some() => {
+            let objective = args.score.unwrap();
+            let on_best = |mined: &MinedSalt, score: u32| {
+                if !args.quiet {
+                    let address = compute_strategy_address(strategy_address, init_code_hash, &derivation, mined.salt);
+                    clear_progress();
+                    eprintln!(
+                        "New best after {} attempts: {} with {} {}",
+                        mined.attempts,
+                        address.to_checksum(None),
+                        score,
+                        objective
+                    );
+                }
+            };
+            let mined = miner
+                .mine_best(seed, threads, objective, args.limits(), on_best, on_progress);
+            clear_progress();
+            let mined = mined.unwrap_or_else(|err| exit_with_search_error(&err, 0, start.elapsed(), args.format));
+            (mined.salt, mined.attempts)
+        }
+        None => {
+            // Checkpoint the search with every progress report, including the last one when a limit is reached
+            let on_progress = |progress: SearchProgress| {
+                on_progress(progress.attempts);
+                if let Some(checkpoint_path) = &args.checkpoint {
+                    let checkpoint = Checkpoint {
+                        key: CacheKey { seed: Some(seed), ..cache_key.clone() },
+                        init_code_hash,
+                        shard,
+                        next_block: progress.next_block,
+                        attempts: previous_attempts + progress.attempts,
+                    };
+                    if let Err(err) = checkpoint.save(checkpoint_path) {
+                        eprintln!("Warning: {}", err);
+                    }
+                }
+            };
+            let mined =
+                miner.mine_shard(seed, shard, first_block, threads, args.limits(), on_progress);
+            clear_progress();
+            let mined = mined
+                .unwrap_or_else(|err| exit_with_search_error(&err, previous_attempts, start.elapsed(), args.format));
+            if let Some(checkpoint_path) = &args.checkpoint {
+                if let Err(err) = Checkpoint::remove(checkpoint_path) {
+                    eprintln!("Warning: {}", err);
+                }
+            }
+
+            if let Some(cache_path) = &args.cache {
+                if let Err(err) = SaltCache::insert_and_save(cache_path, cache_key, init_code_hash, mined.salt) {
+                    eprintln!("Warning: {}", err);
+                }
             }
-        });
+            (mined.salt, mined.attempts)
+        }
+    };
+    let elapsed = start.elapsed();
 
-        handles.push(handle);
+    // Print results
+    let intermediate_salts = derivation.intermediate_salts(salt);
+    let create2_salt = intermediate_salts.last().copied().unwrap_or(salt);
+    let address = compute_strategy_address(strategy_address, init_code_hash, &derivation, salt);
+    let score = args.score.map(|objective| objective.score(address));
+    if args.format == OutputFormat::Abi {
+        println!("{}", hex::encode_prefixed((salt, address, create2_salt).abi_encode_params()));
+    } else if args.format == OutputFormat::Json {
+        let output = MineOutput {
+            salt,
+            salt_with_msg_sender: intermediate_salts.first().copied().unwrap_or(salt),
+            create2_salt,
+            address: address.to_checksum(None),
+            hook_flags: hook_permissions.flags(),
+            score,
+            cached: cached_salt.is_some(),
+            attempts: previous_attempts + attempts,
+            elapsed_seconds: elapsed.as_secs_f64(),
+            hashrate: Progress { attempts, elapsed, probability }.hashrate(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if !quiet {
+        if cached_salt.is_some() {
+            println!("Salt found in cache!");
+        } else if args.score.is_some() {
+            println!("Best Salt Found!");
+        } else {
+            println!("Salt Found!");
+        }
+        println!(" * Salt: {:?}", salt);
+        print_intermediate_salts(&derivation, &intermediate_salts);
+        println!(" * Address: {}", address.to_checksum(None));
+        if let (Some(objective), Some(score)) = (args.score, score) {
+            println!(" * Score: {} {}", score, objective);
+        }
+        if cached_salt.is_none() {
+            println!(" * Attempts: {} in {:.2}s", previous_attempts + attempts, elapsed.as_secs_f64());
+        }
+    } else {
+        println!("{:?}", salt);
     }
+}
+
+/// Mines `--count` salts for each job of `--manifest` at once, sharing the threads across the jobs
+fn mine_many(args: MineArgs) {
+    let strategy_kind = args.requirements.strategy_kind;
+    let hook_permissions = args.requirements.hook_permissions();
+    let vanity = args.requirements.vanity(hook_permissions);
+    let threads = args.threads.get();
+    let seed = args.seed.unwrap_or_else(|| B256::from(rand::random::<[u8; 32]>()));
+    let count = args.count.unwrap_or(1) as usize;
+    let quiet = args.quiet || args.format != OutputFormat::Text;
+    let jobs: Vec<(Address, SaltDerivation, B256)> = args
+        .jobs()
+        .iter()
+        .map(|job| {
+            let (strategy_address, derivation) = args.deployment.resolve_job(job);
+            (strategy_address, derivation, args.init_code.resolve_job(strategy_kind, job))
+        })
+        .collect();
 
-    // Wait for all threads to complete
-    for handle in handles {
-        handle.join().unwrap();
+    // Print run properties
+    if !quiet {
+        println!("Run properties:");
+        for (i, (_, derivation, init_code_hash)) in jobs.iter().enumerate() {
+            println!(" * Job {}: salt derivation {}, init code hash {:?}", i, derivation, init_code_hash);
+        }
+        if let Some(strategy_kind) = strategy_kind {
+            println!(" * Strategy kind: {}", strategy_kind);
+        }
+        println!(" * Hook permissions: {}", &hook_permissions);
+        println!(" * Strategy address: {:?}", &jobs[0].0);
+        println!(" * Seed: {:?}", &seed);
+        println!(" * Salts per job: {}", count);
+        if !vanity.is_empty() {
+            println!(" * Vanity: {}", &vanity);
+        }
+        println!(" * Number of threads: {}", threads);
+        match success_probability(hook_permissions, &vanity) {
+            Some(probability) => println!(" * Expected attempts per salt: ~{:.0}", 1.0 / probability),
+            None => println!(" * Expected attempts per salt: unknown with a regex"),
+        }
+        if let Some(timeout) = args.timeout {
+            println!(" * Timeout: {}s", timeout);
+        }
+        if let Some(max_attempts) = args.max_attempts {
+            println!(" * Max attempts per job: {}", max_attempts);
+        }
+        println!();
     }
-    // If not quiet then the spinner will be some and we should stop it
-    if let Some(ref mut spinner) = sp { spinner.stop() };
+
+    let miners: Vec<SaltMiner> = jobs
+        .iter()
+        .map(|(strategy_address, derivation, init_code_hash)| {
+            SaltMiner::new(*strategy_address, *init_code_hash, hook_permissions, derivation, &vanity)
+        })
+        .collect();
+
+    let start = Instant::now();
+    let on_progress = |attempts: u64| {
+        if !args.quiet {
+            print_progress(&Progress { attempts, elapsed: start.elapsed(), probability: None });
+        }
+    };
+    let mined = mine_batch(&miners, count, seed, threads, args.limits(), on_progress);
+    clear_progress();
+    let mined = mined.unwrap_or_else(|err| exit_with_search_error(&err, 0, start.elapsed(), args.format));
+    let elapsed = start.elapsed();
+    let attempts = mined.iter().map(|salts| salts[0].attempts).sum();
 
     // Print results
-    let salt = shared_salt.read().unwrap();
-    if !quiet {
-        println!("\n\nSalt Found!");
-        let salt_with_msg_sender = abi_encode_sender_and_salt(msg_sender_address, *salt);
-        let salt_with_token_launcher = abi_encode_sender_and_salt(token_launcher_address, salt_with_msg_sender);
+    let (salts, addresses): (Vec<BatchSaltOutput>, Vec<Address>) = jobs
+        .iter()
+        .zip(&mined)
+        .enumerate()
+        .flat_map(|(job, ((strategy_address, derivation, init_code_hash), salts))| {
+            salts.iter().map(move |mined| {
+                let intermediate_salts = derivation.intermediate_salts(mined.salt);
+                let address = compute_strategy_address(*strategy_address, *init_code_hash, derivation, mined.salt);
+                let output = BatchSaltOutput {
+                    job,
+                    salt: mined.salt,
+                    salt_with_msg_sender: intermediate_salts.first().copied().unwrap_or(mined.salt),
+                    create2_salt: intermediate_salts.last().copied().unwrap_or(mined.salt),
+                    address: address.to_checksum(None),
+                };
+                (output, address)
+            })
+        })
+        .unzip();
+    if args.format == OutputFormat::Abi {
+        let encoded = (
+            salts.iter().map(|output| output.salt).collect::<Vec<_>>(),
+            addresses,
+            salts.iter().map(|output| output.create2_salt).collect::<Vec<_>>(),
+        )
+            .abi_encode_params();
+        println!("{}", hex::encode_prefixed(encoded));
+    } else if args.format == OutputFormat::Json {
+        let output = BatchOutput {
+            salts,
+            hook_flags: hook_permissions.flags(),
+            attempts,
+            elapsed_seconds: elapsed.as_secs_f64(),
+            hashrate: Progress { attempts, elapsed, probability: None }.hashrate(),
+        };
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if !quiet {
+        println!("Salts Found!");
+        for output in &salts {
+            println!(" * Job {}: salt {:?}, address {}", output.job, output.salt, output.address);
+        }
+        println!(" * Attempts: {} in {:.2}s", attempts, elapsed.as_secs_f64());
+    } else {
+        for output in &salts {
+            println!("{:?}", output.salt);
+        }
+    }
+}
+
+fn predict(args: PredictArgs) {
+    let (strategy_address, derivation) = args.deployment.resolve();
+    let init_code_hash = args.init_code.resolve(args.strategy_kind);
+    let salt = args.salt;
+
+    let address = compute_strategy_address(strategy_address, init_code_hash, &derivation, salt);
+
+    if !args.quiet {
+        println!("Predicted strategy address:");
+        println!(" * Init code hash: {:?}", init_code_hash);
         println!(" * Salt: {:?}", salt);
-        println!(" * Salt with msg sender: {:?}", salt_with_token_launcher);
-        println!(" * Salt with token launcher: {:?}", salt_with_token_launcher);
-        println!(
-            " * Address: {}",
-            strategy_address
-                .create2(salt_with_token_launcher, init_code_hash)
-                .to_checksum(None)
-        );
+        print_intermediate_salts(&derivation, &derivation.intermediate_salts(salt));
+        println!(" * Address: {}", address.to_checksum(None));
     } else {
-        println!("{:?}", salt);
+        println!("{}", address.to_checksum(None));
+    }
+}
+
+fn verify(args: VerifyArgs) {
+    let (strategy_address, derivation) = args.deployment.resolve();
+    let init_code_hash = args.init_code.resolve(args.requirements.strategy_kind);
+    let hook_permissions = args.requirements.hook_permissions();
+    let vanity = args.requirements.vanity(hook_permissions);
+
+    let verification = verify_salt(
+        strategy_address,
+        init_code_hash,
+        hook_permissions,
+        &derivation,
+        &vanity,
+        args.salt,
+    );
+
+    if !args.quiet {
+        println!("Salt verification:");
+        println!(" * Init code hash: {:?}", init_code_hash);
+        println!(" * Salt: {:?}", verification.salt);
+        print_intermediate_salts(&derivation, &verification.intermediate_salts);
+        println!(" * Address: {}", verification.address.to_checksum(None));
+        println!(" * Hook permissions set: {}", verification.hook_permissions);
+        println!(" * Hook permissions required: {}", verification.required_hook_permissions);
+        match verification.hook_address_error {
+            Some(err) => println!(" * Valid hook address: false ({})", err),
+            None => println!(" * Valid hook address: true"),
+        }
+        if !vanity.is_empty() {
+            println!(" * Vanity {} matched: {}", vanity, verification.fulfills_vanity);
+        }
+        println!();
     }
-}
\ No newline at end of file
+
+    if !verification.is_valid() {
+        eprintln!("Error: Salt does not fulfill the requirements");
+        std::process::exit(EXIT_ERROR);
+    }
+    if !args.quiet {
+        println!("Salt is valid!");
+    } else {
+        println!("{}", verification.address.to_checksum(None));
+    }
+}
+
+fn simulate_migration(args: SimulateMigrationArgs) {
+    let migrator_params = args.migrator_params();
+    let strategy = LBPStrategy::new(args.token, args.total_supply(), &migrator_params)
+        .unwrap_or_else(|err| exit_with_error(format!("{} reverts with {}", args.strategy_kind, err)));
+    let data = strategy
+        .prepare_migration_data(&args.outcome.lbp_params())
+        .unwrap_or_else(|err| exit_with_error(format!("Migration reverts with {}", err)));
+    let one_sided_positions = args.one_sided_positions();
+    let positions = one_sided_positions.map(|(token, currency)| {
+        plan_advanced_positions(&strategy, &data, token, currency)
+            .unwrap_or_else(|err| exit_with_error(format!("Migration reverts with {}", err)))
+    });
+    let plan = args.strategy.map(|strategy_address| {
+        let plan = match one_sided_positions {
+            Some((token, currency)) => advanced_migration_plan(&strategy, &data, strategy_address, token, currency)
+                .unwrap_or_else(|err| exit_with_error(format!("Migration reverts with {}", err))),
+            None => full_range_migration_plan(&strategy, &data, strategy_address),
+        };
+        hex::encode_prefixed(plan.encode())
+    });
+
+    match args.format {
+        OutputFormat::Abi => println!("{}", hex::encode_prefixed(data.abi_encode())),
+        OutputFormat::Json => {
+            let output = MigrationOutput {
+                currency_is_currency0: strategy.currency_is_currency0(),
+                reserve_token_amount: strategy.reserve_token_amount.to_string(),
+                sqrt_price_x96: data.sqrtPriceX96.to_string(),
+                full_range_token_amount: data.fullRangeTokenAmount.to_string(),
+                full_range_currency_amount: data.fullRangeCurrencyAmount.to_string(),
+                leftover_currency: data.leftoverCurrency.to_string(),
+                liquidity: data.liquidity.to_string(),
+                positions: positions.map(|positions| PositionsOutput {
+                    full_range: PositionOutput::minted(&positions.full_range, None),
+                    one_sided_token: PositionOutput::one_sided(&positions.one_sided_token),
+                    one_sided_currency: PositionOutput::one_sided(&positions.one_sided_currency),
+                    token_transfer_amount: positions.token_transfer_amount.to_string(),
+                    currency_transfer_amount: positions.currency_transfer_amount.to_string(),
+                }),
+                plan,
+            };
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        }
+        OutputFormat::Text => {
+            println!("Simulated migration:");
+            println!(" * Currency is currency0: {}", strategy.currency_is_currency0());
+            println!(" * Reserve token amount: {}", strategy.reserve_token_amount);
+            println!(" * Sqrt price X96: {}", data.sqrtPriceX96);
+            println!(" * Full range token amount: {}", data.fullRangeTokenAmount);
+            println!(" * Full range currency amount: {}", data.fullRangeCurrencyAmount);
+            println!(" * Leftover currency: {}", data.leftoverCurrency);
+            println!(" * Liquidity: {}", data.liquidity);
+            if let Some(positions) = positions {
+                let full_range = positions.full_range;
+                println!(
+                    " * Full range position: ticks {}, liquidity {}",
+                    full_range.bounds, full_range.liquidity
+                );
+                print_one_sided_position("token", &positions.one_sided_token);
+                print_one_sided_position("currency", &positions.one_sided_currency);
+                println!(" * Token transfer amount: {}", positions.token_transfer_amount);
+                println!(" * Currency transfer amount: {}", positions.currency_transfer_amount);
+            }
+            if let Some(plan) = plan {
+                println!(" * Plan: {}", plan);
+            }
+        }
+    }
+}
+
+fn print_one_sided_position(side: &str, position: &OneSidedPosition) {
+    match position {
+        OneSidedPosition::Minted { position, amount } => println!(
+            " * One-sided {} position: ticks {}, liquidity {}, amount {}",
+            side, position.bounds, position.liquidity, amount
+        ),
+        OneSidedPosition::Skipped(reason) => println!(" * One-sided {} position: skipped, {}", side, reason),
+    }
+}
+
+fn print_intermediate_salts(derivation: &SaltDerivation, intermediate_salts: &[B256]) {
+    for (sender, salt) in derivation.senders().iter().zip(intermediate_salts) {
+        println!(" * Salt with {:?}: {:?}", sender, salt);
+    }
+}
+
+/// Prints the limit the search reached on stderr, as JSON unless the output format is text, and exits with its code.
+/// `previous_attempts` are those of the runs before a resumed search, added to the reported attempts.
+fn exit_with_search_error(err: &SearchError, previous_attempts: u64, elapsed: Duration, format: OutputFormat) -> ! {
+    let exit_code = search_exit_code(err);
+    if format == OutputFormat::Text {
+        eprintln!("Error: {}", err);
+    } else {
+        let output = SearchErrorOutput {
+            error: match err {
+                SearchError::Timeout { .. } => "timeout",
+                SearchError::MaxAttempts { .. } => "maxAttempts",
+            },
+            message: err.to_string(),
+            exit_code,
+            attempts: previous_attempts + err.attempts(),
+            elapsed_seconds: elapsed.as_secs_f64(),
+        };
+        eprintln!("{}", serde_json::to_string(&output).unwrap());
+    }
+    std::process::exit(exit_code);
+}
+
+/// Prints the progress on stderr, overwriting the previous progress line on a terminal
+fn print_progress(progress: &Progress) {
+    if io::stderr().is_terminal() {
+        eprint!("\r\x1b[2K{}", progress);
+    } else {
+        eprintln!("{}", progress);
+    }
+}
+
+/// Clears the progress line before anything else is printed
+fn clear_progress() {
+    if io::stderr().is_terminal() {
+        eprint!("\r\x1b[2K");
+    }
+}
@@ -0,0 +1,43 @@
+use alloy_primitives::{Address, Bytes};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+    Empty(PathBuf),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io(path, err) => write!(f, "Failed to read manifest {}: {}", path.display(), err),
+            ManifestError::Json(path, err) => write!(f, "Failed to parse manifest {}: {}", path.display(), err),
+            ManifestError::Empty(path) => write!(f, "Manifest {} has no jobs", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// A launch to mine salts for in a batch, the fields not set are taken from the command line
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ManifestJob {
+    pub msg_sender: Option<Address>,
+    pub token: Option<Address>,
+    pub config_data: Option<Bytes>,
+}
+
+/// Loads the jobs of a manifest, a JSON array such as `[{"msgSender": "0x..", "token": "0x..", "configData": "0x.."}]`
+pub fn load_manifest(path: &Path) -> Result<Vec<ManifestJob>, ManifestError> {
+    let json = fs::read_to_string(path).map_err(|err| ManifestError::Io(path.to_path_buf(), err))?;
+    let jobs: Vec<ManifestJob> =
+        serde_json::from_str(&json).map_err(|err| ManifestError::Json(path.to_path_buf(), err))?;
+    if jobs.is_empty() {
+        return Err(ManifestError::Empty(path.to_path_buf()));
+    }
+    Ok(jobs)
+}
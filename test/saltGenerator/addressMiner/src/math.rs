@@ -0,0 +1,42 @@
+use alloy_primitives::{U256, U512};
+
+/// Returns `floor(a * b / denominator)` with a 512-bit intermediate product, same as v4-core `FullMath.mulDiv`.
+/// Returns `None` where `mulDiv` reverts, if the denominator is zero or the result overflows 256 bits.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let quotient = U512::from(a) * U512::from(b) / U512::from(denominator);
+    U256::checked_from_limbs_slice(quotient.as_limbs())
+}
+
+/// Returns `floor(sqrt(a))`, same as OpenZeppelin `Math.sqrt`
+pub fn sqrt(a: U256) -> U256 {
+    a.root(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_rounds_down_with_a_512_bit_product() {
+        assert_eq!(mul_div(U256::from(10), U256::from(10), U256::from(3)), Some(U256::from(33)));
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX), Some(U256::MAX));
+        assert_eq!(mul_div(U256::MAX, U256::from(2), U256::from(4)), Some(U256::MAX >> 1));
+    }
+
+    #[test]
+    fn mul_div_fails_where_full_math_reverts() {
+        assert_eq!(mul_div(U256::from(1), U256::from(1), U256::ZERO), None);
+        assert_eq!(mul_div(U256::MAX, U256::from(2), U256::from(1)), None);
+    }
+
+    #[test]
+    fn sqrt_rounds_down() {
+        assert_eq!(sqrt(U256::ZERO), U256::ZERO);
+        assert_eq!(sqrt(U256::from(15)), U256::from(3));
+        assert_eq!(sqrt(U256::from(16)), U256::from(4));
+        assert_eq!(sqrt(U256::MAX), U256::from(u128::MAX));
+    }
+}
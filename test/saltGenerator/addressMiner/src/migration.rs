@@ -0,0 +1,403 @@
+use crate::positions::BasePositionParams;
+use crate::pricing::{calculate_amounts, convert_to_price_x192, convert_to_sqrt_price_x96, PricingError};
+use crate::ticks::{
+    get_liquidity_for_amounts, get_sqrt_price_at_tick, max_usable_tick, min_usable_tick, TickError, MAX_TICK_SPACING,
+    MIN_TICK_SPACING,
+};
+use crate::types::{LBPInitializationParams, MigrationData, MigratorParameters};
+use alloy_primitives::{address, Address, U256};
+use std::fmt;
+
+/// `TokenDistribution.MAX_TOKEN_SPLIT`, 100% in mps
+pub const MAX_TOKEN_SPLIT: u32 = 10_000_000;
+/// `LPFeeLibrary.MAX_LP_FEE`, 100% in hundredths of a bip
+pub const MAX_LP_FEE: u32 = 1_000_000;
+/// `ActionConstants.MSG_SENDER`, reserved by the position manager
+const MSG_SENDER: Address = address!("0000000000000000000000000000000000000001");
+/// `ActionConstants.ADDRESS_THIS`, reserved by the position manager
+const ADDRESS_THIS: Address = address!("0000000000000000000000000000000000000002");
+
+/// The reverts of the LBP strategy constructor and migration, with the same parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationError {
+    InvalidSweepBlock { sweep_block: u64, migration_block: u64 },
+    MaxCurrencyAmountForLPIsZero,
+    TokenSplitTooHigh { token_split: u32, max_token_split: u32 },
+    InvalidTickSpacing { tick_spacing: i32, min_tick_spacing: i32, max_tick_spacing: i32 },
+    InvalidFee { fee: u32, max_fee: u32 },
+    InvalidPositionRecipient { position_recipient: Address },
+    InitializerTokenSplitIsZero,
+    CurrencyAmountTooHigh { currency_amount: U256, max_currency_amount: u128 },
+    NoCurrencyRaised,
+    /// Checked arithmetic overflowing, a `Panic(0x11)`
+    ArithmeticOverflow,
+    Pricing(PricingError),
+    Tick(TickError),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::InvalidSweepBlock { sweep_block, migration_block } => {
+                write!(f, "InvalidSweepBlock({}, {})", sweep_block, migration_block)
+            }
+            MigrationError::MaxCurrencyAmountForLPIsZero => write!(f, "MaxCurrencyAmountForLPIsZero()"),
+            MigrationError::TokenSplitTooHigh { token_split, max_token_split } => {
+                write!(f, "TokenSplitTooHigh({}, {})", token_split, max_token_split)
+            }
+            MigrationError::InvalidTickSpacing { tick_spacing, min_tick_spacing, max_tick_spacing } => {
+                write!(f, "InvalidTickSpacing({}, {}, {})", tick_spacing, min_tick_spacing, max_tick_spacing)
+            }
+            MigrationError::InvalidFee { fee, max_fee } => write!(f, "InvalidFee({}, {})", fee, max_fee),
+            MigrationError::InvalidPositionRecipient { position_recipient } => {
+                write!(f, "InvalidPositionRecipient({})", position_recipient)
+            }
+            MigrationError::InitializerTokenSplitIsZero => write!(f, "InitializerTokenSplitIsZero()"),
+            MigrationError::CurrencyAmountTooHigh { currency_amount, max_currency_amount } => {
+                write!(f, "CurrencyAmountTooHigh({}, {})", currency_amount, max_currency_amount)
+            }
+            MigrationError::NoCurrencyRaised => write!(f, "NoCurrencyRaised()"),
+            MigrationError::ArithmeticOverflow => write!(f, "Panic(0x11): arithmetic overflow"),
+            MigrationError::Pricing(err) => err.fmt(f),
+            MigrationError::Tick(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<PricingError> for MigrationError {
+    fn from(err: PricingError) -> Self {
+        MigrationError::Pricing(err)
+    }
+}
+
+impl From<TickError> for MigrationError {
+    fn from(err: TickError) -> Self {
+        MigrationError::Tick(err)
+    }
+}
+
+/// `TokenDistribution.calculateTokenSplit`: the part of the total supply sent to the initializer
+pub fn calculate_token_split(total_supply: u128, split_mps: u32) -> u128 {
+    // At most the total supply, as the split is validated to be below `MAX_TOKEN_SPLIT`
+    (U256::from(total_supply) * U256::from(split_mps) / U256::from(MAX_TOKEN_SPLIT)).to::<u128>()
+}
+
+/// `TokenDistribution.calculateReserveSupply`: the part of the total supply kept for liquidity
+pub fn calculate_reserve_supply(total_supply: u128, split_mps: u32) -> u128 {
+    total_supply - calculate_token_split(total_supply, split_mps)
+}
+
+/// The immutables `LBPStrategyBase` sets in its constructor that the migration depends on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LBPStrategy {
+    /// The token of the pool, the underlying token of a virtual token
+    pub pool_token: Address,
+    pub currency: Address,
+    pub total_supply: u128,
+    pub reserve_token_amount: u128,
+    pub max_currency_amount_for_lp: u128,
+    pub pool_lp_fee: u32,
+    pub pool_tick_spacing: i32,
+    pub position_recipient: Address,
+}
+
+impl LBPStrategy {
+    /// Mirrors the constructor of `LBPStrategyBase`, failing with the revert of `_validateMigratorParams`
+    pub fn new(
+        pool_token: Address,
+        total_supply: u128,
+        migrator_params: &MigratorParameters,
+    ) -> Result<Self, MigrationError> {
+        validate_migrator_params(total_supply, migrator_params)?;
+        Ok(Self {
+            pool_token,
+            currency: migrator_params.currency,
+            total_supply,
+            reserve_token_amount: calculate_reserve_supply(total_supply, migrator_params.tokenSplit.to()),
+            max_currency_amount_for_lp: migrator_params.maxCurrencyAmountForLP,
+            pool_lp_fee: migrator_params.poolLPFee.to(),
+            pool_tick_spacing: migrator_params.poolTickSpacing.as_i32(),
+            position_recipient: migrator_params.positionRecipient,
+        })
+    }
+
+    /// `_currencyIsCurrency0`
+    pub fn currency_is_currency0(&self) -> bool {
+        self.currency < self.pool_token
+    }
+
+    /// `_basePositionParams`, `hooks` being the address the strategy is deployed to
+    pub fn base_position_params(&self, data: &MigrationData, hooks: Address) -> BasePositionParams {
+        BasePositionParams {
+            currency: self.currency,
+            pool_token: self.pool_token,
+            pool_lp_fee: self.pool_lp_fee,
+            pool_tick_spacing: self.pool_tick_spacing,
+            initial_sqrt_price_x96: data.sqrtPriceX96,
+            liquidity: data.liquidity,
+            position_recipient: self.position_recipient,
+            hooks,
+        }
+    }
+
+    /// Mirrors `_prepareMigrationData` after the checks of `_validateMigration` on the raised currency.
+    /// The migration block and the currency balance of the strategy are not checked, they depend on the chain.
+    pub fn prepare_migration_data(
+        &self,
+        lbp_params: &LBPInitializationParams,
+    ) -> Result<MigrationData, MigrationError> {
+        let max_currency_amount = u128::MAX;
+        if lbp_params.currencyRaised > U256::from(max_currency_amount) {
+            return Err(MigrationError::CurrencyAmountTooHigh {
+                currency_amount: lbp_params.currencyRaised,
+                max_currency_amount,
+            });
+        }
+        if lbp_params.currencyRaised.is_zero() {
+            return Err(MigrationError::NoCurrencyRaised);
+        }
+
+        let currency_amount = lbp_params.currencyRaised.to::<u128>().min(self.max_currency_amount_for_lp);
+        let currency_is_currency0 = self.currency_is_currency0();
+
+        let price_x192 = convert_to_price_x192(lbp_params.initialPriceX96, currency_is_currency0)?;
+        let sqrt_price_x96 = convert_to_sqrt_price_x96(price_x192)?;
+
+        let (full_range_token_amount, full_range_currency_amount) =
+            calculate_amounts(price_x192, currency_amount, currency_is_currency0, self.reserve_token_amount)?;
+
+        let leftover_currency = currency_amount - full_range_currency_amount;
+
+        let (amount0, amount1) = if currency_is_currency0 {
+            (full_range_currency_amount, full_range_token_amount)
+        } else {
+            (full_range_token_amount, full_range_currency_amount)
+        };
+        let liquidity = get_liquidity_for_amounts(
+            sqrt_price_x96,
+            get_sqrt_price_at_tick(min_usable_tick(self.pool_tick_spacing))?,
+            get_sqrt_price_at_tick(max_usable_tick(self.pool_tick_spacing))?,
+            amount0,
+            amount1,
+        )?;
+
+        Ok(MigrationData {
+            sqrtPriceX96: sqrt_price_x96,
+            fullRangeTokenAmount: full_range_token_amount,
+            fullRangeCurrencyAmount: full_range_currency_amount,
+            leftoverCurrency: leftover_currency,
+            liquidity,
+        })
+    }
+}
+
+/// `LBPStrategyBase._validateMigratorParams`, the checks are in the same order so the first failing one is returned
+pub fn validate_migrator_params(total_supply: u128, params: &MigratorParameters) -> Result<(), MigrationError> {
+    let (token_split, tick_spacing, fee) =
+        (params.tokenSplit.to::<u32>(), params.poolTickSpacing.as_i32(), params.poolLPFee.to::<u32>());
+    if params.sweepBlock <= params.migrationBlock {
+        return Err(MigrationError::InvalidSweepBlock {
+            sweep_block: params.sweepBlock,
+            migration_block: params.migrationBlock,
+        });
+    }
+    if params.maxCurrencyAmountForLP == 0 {
+        return Err(MigrationError::MaxCurrencyAmountForLPIsZero);
+    }
+    if token_split >= MAX_TOKEN_SPLIT {
+        return Err(MigrationError::TokenSplitTooHigh { token_split, max_token_split: MAX_TOKEN_SPLIT });
+    }
+    if !(MIN_TICK_SPACING..=MAX_TICK_SPACING).contains(&tick_spacing) {
+        return Err(MigrationError::InvalidTickSpacing {
+            tick_spacing,
+            min_tick_spacing: MIN_TICK_SPACING,
+            max_tick_spacing: MAX_TICK_SPACING,
+        });
+    }
+    if fee > MAX_LP_FEE {
+        return Err(MigrationError::InvalidFee { fee, max_fee: MAX_LP_FEE });
+    }
+    if [Address::ZERO, MSG_SENDER, ADDRESS_THIS].contains(&params.positionRecipient) {
+        return Err(MigrationError::InvalidPositionRecipient { position_recipient: params.positionRecipient });
+    }
+    if calculate_token_split(total_supply, token_split) == 0 {
+        return Err(MigrationError::InitializerTokenSplitIsZero);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::Q96;
+    use alloy_primitives::aliases::{I24, U24};
+    use alloy_primitives::{uint, U160};
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+    const TOKEN: Address = address!("1111111111111111111111111111111111111111");
+
+    fn migrator_params() -> MigratorParameters {
+        MigratorParameters {
+            migrationBlock: 100,
+            currency: Address::ZERO,
+            poolLPFee: U24::from(3000),
+            poolTickSpacing: I24::unchecked_from(60),
+            tokenSplit: U24::from(5_000_000),
+            initializerFactory: address!("3333333333333333333333333333333333333333"),
+            positionRecipient: address!("2222222222222222222222222222222222222222"),
+            sweepBlock: 200,
+            operator: address!("4444444444444444444444444444444444444444"),
+            maxCurrencyAmountForLP: u128::MAX,
+        }
+    }
+
+    fn outcome(initial_price_x96: U256, currency_raised: u128) -> LBPInitializationParams {
+        LBPInitializationParams {
+            initialPriceX96: initial_price_x96,
+            tokensSold: U256::ZERO,
+            currencyRaised: U256::from(currency_raised),
+        }
+    }
+
+    #[test]
+    fn validate_migrator_params_reverts_like_the_strategy() {
+        let total_supply = 1000 * E18;
+        let validate = |update: fn(&mut MigratorParameters)| {
+            let mut params = migrator_params();
+            update(&mut params);
+            validate_migrator_params(total_supply, &params)
+        };
+        assert_eq!(validate(|_| {}), Ok(()));
+        assert_eq!(
+            validate(|params| params.sweepBlock = params.migrationBlock),
+            Err(MigrationError::InvalidSweepBlock { sweep_block: 100, migration_block: 100 })
+        );
+        assert_eq!(
+            validate(|params| params.maxCurrencyAmountForLP = 0),
+            Err(MigrationError::MaxCurrencyAmountForLPIsZero)
+        );
+        assert_eq!(
+            validate(|params| params.tokenSplit = U24::from(MAX_TOKEN_SPLIT)),
+            Err(MigrationError::TokenSplitTooHigh { token_split: MAX_TOKEN_SPLIT, max_token_split: MAX_TOKEN_SPLIT })
+        );
+        let invalid_tick_spacing = |tick_spacing| MigrationError::InvalidTickSpacing {
+            tick_spacing,
+            min_tick_spacing: MIN_TICK_SPACING,
+            max_tick_spacing: MAX_TICK_SPACING,
+        };
+        assert_eq!(validate(|params| params.poolTickSpacing = I24::ZERO), Err(invalid_tick_spacing(0)));
+        assert_eq!(
+            validate(|params| params.poolTickSpacing = I24::unchecked_from(-60)),
+            Err(invalid_tick_spacing(-60))
+        );
+        assert_eq!(
+            validate(|params| params.poolTickSpacing = I24::unchecked_from(MAX_TICK_SPACING + 1)),
+            Err(invalid_tick_spacing(MAX_TICK_SPACING + 1))
+        );
+        assert_eq!(validate(|params| params.poolTickSpacing = I24::unchecked_from(MAX_TICK_SPACING)), Ok(()));
+        assert_eq!(
+            validate(|params| params.poolLPFee = U24::from(MAX_LP_FEE + 1)),
+            Err(MigrationError::InvalidFee { fee: MAX_LP_FEE + 1, max_fee: MAX_LP_FEE })
+        );
+        assert_eq!(validate(|params| params.poolLPFee = U24::from(MAX_LP_FEE)), Ok(()));
+        for position_recipient in [Address::ZERO, MSG_SENDER, ADDRESS_THIS] {
+            let mut params = migrator_params();
+            params.positionRecipient = position_recipient;
+            assert_eq!(
+                validate_migrator_params(total_supply, &params),
+                Err(MigrationError::InvalidPositionRecipient { position_recipient })
+            );
+        }
+        assert_eq!(validate(|params| params.tokenSplit = U24::ZERO), Err(MigrationError::InitializerTokenSplitIsZero));
+        // Rounded down to zero tokens for the initializer
+        assert_eq!(validate_migrator_params(1, &migrator_params()), Err(MigrationError::InitializerTokenSplitIsZero));
+    }
+
+    #[test]
+    fn validate_migrator_params_returns_the_first_revert() {
+        let mut params = migrator_params();
+        params.positionRecipient = Address::ZERO;
+        params.poolLPFee = U24::from(MAX_LP_FEE + 1);
+        assert!(matches!(validate_migrator_params(0, &params), Err(MigrationError::InvalidFee { .. })));
+        params.poolTickSpacing = I24::ZERO;
+        assert!(matches!(validate_migrator_params(0, &params), Err(MigrationError::InvalidTickSpacing { .. })));
+        params.tokenSplit = U24::from(MAX_TOKEN_SPLIT);
+        assert!(matches!(validate_migrator_params(0, &params), Err(MigrationError::TokenSplitTooHigh { .. })));
+        params.maxCurrencyAmountForLP = 0;
+        assert_eq!(validate_migrator_params(0, &params), Err(MigrationError::MaxCurrencyAmountForLPIsZero));
+        params.sweepBlock = 0;
+        assert!(matches!(validate_migrator_params(0, &params), Err(MigrationError::InvalidSweepBlock { .. })));
+    }
+
+    #[test]
+    fn prepare_migration_data_with_the_currency_as_currency0() {
+        let strategy = LBPStrategy::new(TOKEN, 1000 * E18, &migrator_params()).unwrap();
+        assert_eq!(strategy.reserve_token_amount, 500 * E18);
+        assert_eq!(
+            strategy.prepare_migration_data(&outcome(Q96, 100 * E18)),
+            Ok(MigrationData {
+                sqrtPriceX96: U160::from(Q96),
+                fullRangeTokenAmount: 100 * E18,
+                fullRangeCurrencyAmount: 100 * E18,
+                leftoverCurrency: 0,
+                liquidity: 100 * E18 + 5,
+            })
+        );
+
+        // A token worth ten times the currency, the token amount rounded down
+        let mut params = migrator_params();
+        params.poolTickSpacing = I24::unchecked_from(200);
+        let strategy = LBPStrategy::new(TOKEN, 2000 * E18, &params).unwrap();
+        assert_eq!(
+            strategy.prepare_migration_data(&outcome(Q96 * U256::from(10), 5 * E18)),
+            Ok(MigrationData {
+                sqrtPriceX96: uint!(25054144837504793118641380156_U160),
+                fullRangeTokenAmount: E18 / 2 - 1,
+                fullRangeCurrencyAmount: 5 * E18,
+                leftoverCurrency: 0,
+                liquidity: 1581138830084189663,
+            })
+        );
+    }
+
+    #[test]
+    fn prepare_migration_data_limited_by_the_reserve_and_the_max_currency_amount() {
+        let mut params = migrator_params();
+        params.currency = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+        params.maxCurrencyAmountForLP = 80 * E18;
+        let strategy = LBPStrategy::new(TOKEN, 60 * E18, &params).unwrap();
+        assert!(!strategy.currency_is_currency0());
+        // Two of currency per token, 80 of the 100 raised for 30 tokens of reserve, the leftover being what is left
+        assert_eq!(
+            strategy.prepare_migration_data(&outcome(Q96 * U256::from(2), 100 * E18)),
+            Ok(MigrationData {
+                sqrtPriceX96: uint!(112045541949572279837463876454_U160),
+                fullRangeTokenAmount: 30 * E18,
+                fullRangeCurrencyAmount: 60 * E18,
+                leftoverCurrency: 20 * E18,
+                liquidity: 42426406871192851465,
+            })
+        );
+    }
+
+    #[test]
+    fn prepare_migration_data_reverts_like_the_strategy() {
+        let strategy = LBPStrategy::new(TOKEN, 1000 * E18, &migrator_params()).unwrap();
+        assert_eq!(strategy.prepare_migration_data(&outcome(Q96, 0)), Err(MigrationError::NoCurrencyRaised));
+        let too_high =
+            LBPInitializationParams { currencyRaised: U256::from(u128::MAX) + U256::from(1), ..outcome(Q96, 0) };
+        assert_eq!(
+            strategy.prepare_migration_data(&too_high),
+            Err(MigrationError::CurrencyAmountTooHigh {
+                currency_amount: too_high.currencyRaised,
+                max_currency_amount: u128::MAX
+            })
+        );
+        assert_eq!(
+            strategy.prepare_migration_data(&outcome(U256::ZERO, E18)),
+            Err(MigrationError::Pricing(PricingError::PriceIsZero { price: U256::ZERO }))
+        );
+    }
+}
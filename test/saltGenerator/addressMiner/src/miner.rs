@@ -0,0 +1,616 @@
+use crate::derivation::SaltDerivation;
+use crate::fulfills_requirements;
+use crate::hooks::HookPermissions;
+use crate::score::ScoreObjective;
+use crate::vanity::Vanity;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::{slice, thread};
+use std::time::{Duration, Instant};
+
+/// Number of consecutive salts a thread claims at once
+pub const BLOCK_SIZE: u64 = 1 << 16;
+
+/// Interval at which the number of attempts is reported while mining
+pub const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Returns the salt at the given index of the search starting at the seed, `seed + index` wrapping around
+pub fn salt_at(seed: B256, index: u64) -> B256 {
+    B256::from(U256::from_be_bytes(seed.0).wrapping_add(U256::from(index)))
+}
+
+/// A salt found by `SaltMiner::mine`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinedSalt {
+    pub salt: B256,
+    /// Index of the salt in the search from the seed
+    pub index: u64,
+    /// Number of salts hashed by all the threads, including those above the index
+    pub attempts: u64,
+}
+
+/// Limits on a search, unlimited if not set
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    /// Only the salts at the indices below are searched
+    pub max_attempts: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+/// A part of the search space, every `count`th block of `BLOCK_SIZE` indices starting at block `index`,
+/// so that `count` machines can split a search with the same seed. The default shard is the whole search space.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    pub index: u64,
+    pub count: u64,
+}
+
+impl Shard {
+    /// Returns the first index of the `block`th block of the shard
+    pub fn block_start(&self, block: u64) -> u64 {
+        self.count.saturating_mul(block).saturating_add(self.index).saturating_mul(BLOCK_SIZE)
+    }
+}
+
+impl Default for Shard {
+    fn default() -> Self {
+        Self { index: 0, count: 1 }
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    /// Parses `i/n`, the `i`th of `n` shards counting from 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s.split_once('/').ok_or("expected i/n")?;
+        let index: u64 = index.parse().map_err(|_| format!("invalid shard index {:?}", index))?;
+        let count: u64 = count.parse().map_err(|_| format!("invalid shard count {:?}", count))?;
+        if index >= count {
+            return Err(format!("shard index {} is not below the shard count {}", index, count));
+        }
+        Ok(Self { index, count })
+    }
+}
+
+impl fmt::Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// The progress of a search reported by `SaltMiner::mine_shard`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SearchProgress {
+    /// Number of salts hashed so far
+    pub attempts: u64,
+    /// The first block of the shard not searched yet, all the blocks below it were searched without a match,
+    /// so a search resumed from it returns the same salt
+    pub next_block: u64,
+}
+
+/// The limit a search reached without finding a salt fulfilling the requirements
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SearchError {
+    Timeout { timeout: Duration, attempts: u64 },
+    MaxAttempts { max_attempts: u64, attempts: u64 },
+}
+
+impl SearchError {
+    /// Returns the number of salts hashed before the search stopped
+    pub fn attempts(&self) -> u64 {
+        match self {
+            SearchError::Timeout { attempts, .. } => *attempts,
+            SearchError::MaxAttempts { attempts, .. } => *attempts,
+        }
+    }
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::Timeout { timeout, attempts } => write!(
+                f,
+                "No salt fulfilling the requirements found within the timeout of {}s ({} attempts)",
+                timeout.as_secs(),
+                attempts
+            ),
+            SearchError::MaxAttempts { max_attempts, attempts } => write!(
+                f,
+                "No salt fulfilling the requirements found within the limit of {} attempts ({} attempts)",
+                max_attempts, attempts
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+/// Searches salts for a strategy address fulfilling the requirements.
+/// The preimages of every `abi.encode(sender, salt)` and of the CREATE2 address are laid out once,
+/// each attempt only copies the changing salt into them before hashing.
+#[derive(Clone, Debug)]
+pub struct SaltMiner {
+    // abi.encode(sender, salt), one per wrapping sender with the sender already in place
+    derivation_preimages: Vec<[u8; 64]>,
+    // 0xff ++ strategy factory ++ salt ++ init code hash
+    create2_preimage: [u8; 85],
+    hook_permissions: HookPermissions,
+    vanity: Vanity,
+}
+
+impl SaltMiner {
+    pub fn new(
+        strategy_address: Address,
+        init_code_hash: B256,
+        hook_permissions: HookPermissions,
+        derivation: &SaltDerivation,
+        vanity: &Vanity,
+    ) -> Self {
+        let derivation_preimages = derivation
+            .senders()
+            .iter()
+            .map(|sender| {
+                let mut preimage = [0u8; 64];
+                preimage[12..32].copy_from_slice(sender.as_slice());
+                preimage
+            })
+            .collect();
+
+        let mut create2_preimage = [0u8; 85];
+        create2_preimage[0] = 0xff;
+        create2_preimage[1..21].copy_from_slice(strategy_address.as_slice());
+        create2_preimage[53..].copy_from_slice(init_code_hash.as_slice());
+
+        Self { derivation_preimages, create2_preimage, hook_permissions, vanity: vanity.clone() }
+    }
+
+    /// Computes the strategy address of a salt, same as `compute_strategy_address`
+    pub fn compute_address(&mut self, salt: B256) -> Address {
+        let mut salt = salt;
+        for preimage in &mut self.derivation_preimages {
+            preimage[32..].copy_from_slice(salt.as_slice());
+            salt = keccak256(&preimage[..]);
+        }
+        self.create2_preimage[21..53].copy_from_slice(salt.as_slice());
+        Address::from_word(keccak256(&self.create2_preimage[..]))
+    }
+
+    /// Returns true if the strategy address of the salt fulfills the requirements
+    pub fn fulfills(&mut self, salt: B256) -> bool {
+        self.candidate(salt).is_some()
+    }
+
+    /// Returns the strategy address of the salt if it fulfills the requirements
+    pub fn candidate(&mut self, salt: B256) -> Option<Address> {
+        let address = self.compute_address(salt);
+        fulfills_requirements(address, self.hook_permissions, &self.vanity).then_some(address)
+    }
+
+    /// Searches the salts from the seed upwards on the given number of threads until one fulfills the requirements.
+    /// The threads claim disjoint blocks of `BLOCK_SIZE` indices in increasing order and keep going only while
+    /// their block starts below the best match, so the lowest matching index is returned on any thread count.
+    pub fn mine(&self, seed: B256, threads: usize) -> MinedSalt {
+        self.mine_with_progress(seed, threads, SearchLimits::default(), |_| {})
+            .expect("an unlimited search only returns with a salt")
+    }
+
+    /// Same as `mine`, giving up once a limit is reached and calling `on_progress` with the number of attempts so far
+    /// every `PROGRESS_INTERVAL`. The timeout is checked before claiming each block of `BLOCK_SIZE` indices and every
+    /// claimed block is searched, so a salt returned before the timeout is still the lowest matching one.
+    pub fn mine_with_progress(
+        &self,
+        seed: B256,
+        threads: usize,
+        limits: SearchLimits,
+        on_progress: impl Fn(u64) + Sync,
+    ) -> Result<MinedSalt, SearchError> {
+        self.mine_shard(seed, Shard::default(), 0, threads, limits, |progress| on_progress(progress.attempts))
+    }
+
+    /// Same as `mine_with_progress` on the blocks of a shard from `first_block` on, e.g. the `next_block` of
+    /// the last progress of an interrupted search. `on_progress` is also called once the search stops.
+    pub fn mine_shard(
+        &self,
+        seed: B256,
+        shard: Shard,
+        first_block: u64,
+        threads: usize,
+        limits: SearchLimits,
+        on_progress: impl Fn(SearchProgress) + Sync,
+    ) -> Result<MinedSalt, SearchError> {
+        let on_progress = |attempts: u64, searches: &[BatchSearch]| {
+            on_progress(SearchProgress { attempts, next_block: searches[0].next_unsearched_block() });
+        };
+        let mut salts = search(slice::from_ref(self), 1, seed, shard, first_block, threads, limits, on_progress)?;
+        Ok(salts.remove(0).remove(0))
+    }
+
+    /// Searches the salts from the seed upwards for the address fulfilling the requirements with the highest score,
+    /// until a limit is reached. `on_best` is called with every salt improving the best score so far and
+    /// `on_progress` with the number of attempts so far every `PROGRESS_INTERVAL`.
+    /// Ties go to the lowest index, so with an attempt limit only the result is the same on any thread count.
+    /// The timeout is checked between blocks of `BLOCK_SIZE` indices.
+    pub fn mine_best(
+        &self,
+        seed: B256,
+        threads: usize,
+        objective: ScoreObjective,
+        limits: SearchLimits,
+        on_best: impl Fn(&MinedSalt, u32) + Sync,
+        on_progress: impl Fn(u64) + Sync,
+    ) -> Result<MinedSalt, SearchError> {
+        let deadline = limits.timeout.map(|timeout| Instant::now() + timeout);
+        let max_attempts = limits.max_attempts.unwrap_or(u64::MAX);
+        let next_block = AtomicU64::new(0);
+        let attempts = AtomicU64::new(0);
+        let exhausted = AtomicBool::new(false);
+        // The score and index of the best salt
+        let best = Mutex::new(None::<(u32, u64)>);
+
+        let on_tick = || on_progress(attempts.load(Ordering::Relaxed));
+        run_threads(threads, on_tick, || {
+            let mut miner = self.clone();
+            loop {
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return;
+                }
+                let start = next_block.fetch_add(1, Ordering::Relaxed).saturating_mul(BLOCK_SIZE);
+                if start >= max_attempts {
+                    exhausted.store(true, Ordering::Relaxed);
+                    return;
+                }
+                let end = start.saturating_add(BLOCK_SIZE).min(max_attempts);
+                for index in start..end {
+                    let salt = salt_at(seed, index);
+                    let Some(address) = miner.candidate(salt) else {
+                        continue;
+                    };
+                    let score = objective.score(address);
+                    let mut best = best.lock().unwrap();
+                    let improves = best.is_none_or(|(best_score, _)| score > best_score);
+                    let ties_lower =
+                        best.is_some_and(|(best_score, best_index)| score == best_score && index < best_index);
+                    if improves || ties_lower {
+                        *best = Some((score, index));
+                    }
+                    if improves {
+                        let attempts = attempts.load(Ordering::Relaxed) + index - start + 1;
+                        on_best(&MinedSalt { salt, index, attempts }, score);
+                    }
+                }
+                attempts.fetch_add(end - start, Ordering::Relaxed);
+            }
+        });
+
+        let attempts = attempts.into_inner();
+        best.into_inner()
+            .unwrap()
+            .map(|(_, index)| MinedSalt { salt: salt_at(seed, index), index, attempts })
+            .ok_or_else(|| limit_reached(limits, exhausted.into_inner(), attempts))
+    }
+}
+
+/// Searches the `count` lowest matching salts from the seed upwards for each miner, one miner after the other on a
+/// shared pool of threads. Like `SaltMiner::mine`, a thread keeps claiming blocks of a miner only while they start
+/// below its `count`th match so far, then moves on to the next miner, so the salts are the same on any thread count.
+/// The salts of each miner are in increasing index order, the attempts of each being those made for its miner.
+/// Every miner searches the indices below `max_attempts`, the timeout is checked between blocks of `BLOCK_SIZE`.
+pub fn mine_batch(
+    miners: &[SaltMiner],
+    count: usize,
+    seed: B256,
+    threads: usize,
+    limits: SearchLimits,
+    on_progress: impl Fn(u64) + Sync,
+) -> Result<Vec<Vec<MinedSalt>>, SearchError> {
+    search(miners, count, seed, Shard::default(), 0, threads, limits, |attempts, _| on_progress(attempts))
+}
+
+/// Searches the `count` lowest matching salts of each miner in the blocks of the shard from `first_block` on,
+/// see `mine_batch`
+#[allow(clippy::too_many_arguments)]
+fn search(
+    miners: &[SaltMiner],
+    count: usize,
+    seed: B256,
+    shard: Shard,
+    first_block: u64,
+    threads: usize,
+    limits: SearchLimits,
+    on_progress: impl Fn(u64, &[BatchSearch]) + Sync,
+) -> Result<Vec<Vec<MinedSalt>>, SearchError> {
+    let deadline = limits.timeout.map(|timeout| Instant::now() + timeout);
+    let max_attempts = limits.max_attempts.unwrap_or(u64::MAX);
+    let searches: Vec<BatchSearch> = miners.iter().map(|_| BatchSearch::new(first_block)).collect();
+    let attempts = AtomicU64::new(0);
+
+    let on_tick = || on_progress(attempts.load(Ordering::Relaxed), &searches);
+    run_threads(threads, on_tick, || {
+        for (miner, search) in miners.iter().zip(&searches) {
+            let mut miner = miner.clone();
+            loop {
+                // Checked before claiming a block, so that every claimed block is searched
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    return;
+                }
+                let block = search.next_block.fetch_add(1, Ordering::Relaxed);
+                let start = shard.block_start(block);
+                if start >= max_attempts {
+                    search.exhausted.store(true, Ordering::Relaxed);
+                    break;
+                }
+                if start >= search.threshold.load(Ordering::Relaxed) {
+                    break;
+                }
+                let end = start.saturating_add(BLOCK_SIZE).min(max_attempts);
+                let mut index = start;
+                let mut found = false;
+                while index < end && index <= search.threshold.load(Ordering::Relaxed) {
+                    if miner.fulfills(salt_at(seed, index)) {
+                        search.insert(index, count);
+                        found = true;
+                    }
+                    index += 1;
+                }
+                if !found && index == start + BLOCK_SIZE {
+                    search.searched(block);
+                }
+                search.attempts.fetch_add(index - start, Ordering::Relaxed);
+                attempts.fetch_add(index - start, Ordering::Relaxed);
+            }
+        }
+    });
+
+    searches
+        .into_iter()
+        .map(|search| {
+            let attempts = search.attempts.into_inner();
+            let found = search.found.into_inner().unwrap();
+            if found.len() < count {
+                return Err(limit_reached(limits, search.exhausted.into_inner(), attempts));
+            }
+            Ok(found.into_iter().map(|index| MinedSalt { salt: salt_at(seed, index), index, attempts }).collect())
+        })
+        .collect()
+}
+
+/// The search for the salts of one miner in `mine_batch`
+struct BatchSearch {
+    next_block: AtomicU64,
+    // The lowest matching indices so far in increasing order, at most `count` of them
+    found: Mutex<Vec<u64>>,
+    // The `count`th lowest matching index once found, as no match above it can be among the lowest
+    threshold: AtomicU64,
+    attempts: AtomicU64,
+    // Set once a block starting at or above `max_attempts` is claimed, as all the blocks below it were then
+    exhausted: AtomicBool,
+    // The first block not searched in full without a match, and the blocks above it that were
+    searched_blocks: Mutex<(u64, BTreeSet<u64>)>,
+}
+
+impl BatchSearch {
+    fn new(first_block: u64) -> Self {
+        Self {
+            next_block: AtomicU64::new(first_block),
+            found: Mutex::new(Vec::new()),
+            threshold: AtomicU64::new(u64::MAX),
+            attempts: AtomicU64::new(0),
+            exhausted: AtomicBool::new(false),
+            searched_blocks: Mutex::new((first_block, BTreeSet::new())),
+        }
+    }
+
+    fn insert(&self, index: u64, count: usize) {
+        let mut found = self.found.lock().unwrap();
+        let position = found.partition_point(|found_index| *found_index < index);
+        found.insert(position, index);
+        found.truncate(count);
+        if found.len() == count {
+            self.threshold.fetch_min(found[count - 1], Ordering::Relaxed);
+        }
+    }
+
+    /// Records a block searched in full without a match
+    fn searched(&self, block: u64) {
+        let mut searched_blocks = self.searched_blocks.lock().unwrap();
+        let (next_unsearched, above) = &mut *searched_blocks;
+        above.insert(block);
+        while above.remove(next_unsearched) {
+            *next_unsearched += 1;
+        }
+    }
+
+    fn next_unsearched_block(&self) -> u64 {
+        self.searched_blocks.lock().unwrap().0
+    }
+}
+
+/// Returns the limit a search without a match stopped at, the attempt limit if every index below it was searched.
+/// `attempts` only counts the indices of this run, fewer than the limit for a shard or a resumed search.
+fn limit_reached(limits: SearchLimits, exhausted: bool, attempts: u64) -> SearchError {
+    match limits.timeout {
+        Some(timeout) if !exhausted => SearchError::Timeout { timeout, attempts },
+        _ => SearchError::MaxAttempts { max_attempts: limits.max_attempts.unwrap_or(u64::MAX), attempts },
+    }
+}
+
+/// Runs the worker on the given number of threads and calls `on_tick` every `PROGRESS_INTERVAL` until all of them
+/// return, then once more
+fn run_threads(threads: usize, on_tick: impl Fn() + Sync, worker: impl Fn() + Sync) {
+    let (done, ticks) = mpsc::channel::<()>();
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1)).map(|_| scope.spawn(&worker)).collect();
+        let on_tick = &on_tick;
+        scope.spawn(move || {
+            // Times out until the sender is dropped once the workers are done
+            while ticks.recv_timeout(PROGRESS_INTERVAL) == Err(RecvTimeoutError::Timeout) {
+                on_tick();
+            }
+        });
+        let results: Vec<_> = workers.into_iter().map(|worker| worker.join()).collect();
+        drop(done);
+        for result in results {
+            if let Err(panic) = result {
+                std::panic::resume_unwind(panic);
+            }
+        }
+    });
+    on_tick();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    /// A miner for the before initialize permission alone, about four matches per block
+    fn miner() -> SaltMiner {
+        SaltMiner::new(
+            address!("1111111111111111111111111111111111111111"),
+            B256::repeat_byte(0x22),
+            HookPermissions { before_initialize: true, ..Default::default() },
+            &SaltDerivation::via_token_launcher(
+                address!("3333333333333333333333333333333333333333"),
+                address!("4444444444444444444444444444444444444444"),
+            ),
+            &Vanity::default(),
+        )
+    }
+
+    /// A miner no salt within a few blocks matches
+    fn unmatched_miner() -> SaltMiner {
+        let vanity = Vanity::default().with_prefix("0000000000").unwrap();
+        SaltMiner { vanity, ..miner() }
+    }
+
+    /// The matching indices of the range, found one salt after the other
+    fn matches(seed: B256, indices: std::ops::Range<u64>) -> Vec<u64> {
+        let mut miner = miner();
+        indices.filter(|index| miner.fulfills(salt_at(seed, *index))).collect()
+    }
+
+    #[test]
+    fn mine_returns_the_lowest_match_on_any_thread_count() {
+        let seed = B256::repeat_byte(0x55);
+        let lowest = matches(seed, 0..BLOCK_SIZE)[0];
+        for threads in [1, 2, 3, 8] {
+            let mined = miner().mine(seed, threads);
+            assert_eq!(mined.index, lowest, "{} threads", threads);
+            assert_eq!(mined.salt, salt_at(seed, lowest));
+        }
+    }
+
+    #[test]
+    fn mine_batch_returns_the_lowest_matches_on_any_thread_count() {
+        let seed = B256::ZERO;
+        let lowest = &matches(seed, 0..BLOCK_SIZE)[..3];
+        for threads in [1, 4] {
+            let salts = mine_batch(&[miner()], 3, seed, threads, SearchLimits::default(), |_| {}).unwrap();
+            let indices: Vec<u64> = salts[0].iter().map(|salt| salt.index).collect();
+            assert_eq!(indices, lowest, "{} threads", threads);
+        }
+    }
+
+    #[test]
+    fn shards_partition_the_blocks() {
+        assert_eq!(Shard::default().block_start(3), 3 * BLOCK_SIZE);
+        assert_eq!(Shard { index: 1, count: 3 }.block_start(2), 7 * BLOCK_SIZE);
+        assert_eq!("2/3".parse::<Shard>(), Ok(Shard { index: 2, count: 3 }));
+        assert!("3/3".parse::<Shard>().is_err());
+
+        // Each shard returns the lowest match of its own blocks, the lowest of both being the unsharded one
+        let seed = B256::repeat_byte(0x77);
+        let unsharded = miner().mine(seed, 2).index;
+        let mut lowest = u64::MAX;
+        for index in 0..2 {
+            let shard = Shard { index, count: 2 };
+            let mined = miner().mine_shard(seed, shard, 0, 2, SearchLimits::default(), |_| {}).unwrap();
+            let block = mined.index / BLOCK_SIZE;
+            assert_eq!(block % 2, index);
+            let block_matches = matches(seed, block * BLOCK_SIZE..mined.index + 1);
+            assert_eq!(block_matches, [mined.index]);
+            lowest = lowest.min(mined.index);
+        }
+        assert_eq!(lowest, unsharded);
+    }
+
+    #[test]
+    fn mine_best_returns_the_lowest_index_of_the_best_score_on_any_thread_count() {
+        let seed = B256::ZERO;
+        let objective = ScoreObjective::ZeroNibbles;
+        let max_attempts = 3 * BLOCK_SIZE;
+        let mut expected = None::<(u32, u64)>;
+        let mut scorer = miner();
+        for index in 0..max_attempts {
+            if let Some(address) = scorer.candidate(salt_at(seed, index)) {
+                let score = objective.score(address);
+                if expected.is_none_or(|(best_score, _)| score > best_score) {
+                    expected = Some((score, index));
+                }
+            }
+        }
+        let limits = SearchLimits { max_attempts: Some(max_attempts), timeout: None };
+        for threads in [1, 3] {
+            let mined = miner().mine_best(seed, threads, objective, limits, |_, _| {}, |_| {}).unwrap();
+            assert_eq!(mined.index, expected.unwrap().1, "{} threads", threads);
+            assert_eq!(mined.attempts, max_attempts);
+        }
+    }
+
+    #[test]
+    fn shard_without_a_match_reaches_the_attempt_limit() {
+        let max_attempts = 2 * BLOCK_SIZE;
+        let shard = Shard { index: 0, count: 2 };
+        for timeout in [None, Some(Duration::from_secs(3600))] {
+            let limits = SearchLimits { max_attempts: Some(max_attempts), timeout };
+            let err = unmatched_miner().mine_shard(B256::ZERO, shard, 0, 2, limits, |_| {}).unwrap_err();
+            assert_eq!(err, SearchError::MaxAttempts { max_attempts, attempts: BLOCK_SIZE });
+        }
+    }
+
+    #[test]
+    fn resumed_search_without_a_match_reaches_the_attempt_limit() {
+        let max_attempts = 2 * BLOCK_SIZE;
+        let limits = SearchLimits { max_attempts: Some(max_attempts), timeout: None };
+        let err = unmatched_miner().mine_shard(B256::ZERO, Shard::default(), 1, 2, limits, |_| {}).unwrap_err();
+        assert_eq!(err, SearchError::MaxAttempts { max_attempts, attempts: BLOCK_SIZE });
+    }
+
+    #[test]
+    fn resumed_search_adds_up_to_the_attempts_of_an_uninterrupted_one() {
+        // No match in the first block, the lowest one in the second
+        let seed = B256::repeat_byte(0x02);
+        let vanity = Vanity::default().with_prefix("a").unwrap();
+        let miner = SaltMiner { vanity, ..miner() };
+        let uninterrupted = miner.mine(seed, 1);
+        assert_eq!((uninterrupted.index, uninterrupted.attempts), (69842, 69843));
+
+        // Interrupted after the first block, the checkpoint recording its attempts
+        let limits = SearchLimits { max_attempts: Some(BLOCK_SIZE), timeout: None };
+        let last_progress = Mutex::new(None);
+        let on_progress = |progress| *last_progress.lock().unwrap() = Some(progress);
+        assert!(miner.mine_shard(seed, Shard::default(), 0, 1, limits, on_progress).is_err());
+        let checkpoint = last_progress.into_inner().unwrap().unwrap();
+        assert_eq!(checkpoint, SearchProgress { attempts: BLOCK_SIZE, next_block: 1 });
+
+        let resumed = miner
+            .mine_shard(seed, Shard::default(), checkpoint.next_block, 1, SearchLimits::default(), |_| {})
+            .unwrap();
+        assert_eq!(resumed.index, uninterrupted.index);
+        assert_eq!(checkpoint.attempts + resumed.attempts, uninterrupted.attempts);
+    }
+
+    #[test]
+    fn search_past_the_timeout_claims_no_block() {
+        let limits = SearchLimits { max_attempts: None, timeout: Some(Duration::ZERO) };
+        let err = miner().mine_with_progress(B256::ZERO, 2, limits, |_| {}).unwrap_err();
+        assert_eq!(err, SearchError::Timeout { timeout: Duration::ZERO, attempts: 0 });
+    }
+}
@@ -0,0 +1,335 @@
+use crate::migration::{LBPStrategy, MigrationError};
+use crate::positions::{
+    plan_one_sided_position, BasePositionParams, FullRangeParams, OneSidedParams, OneSidedPosition, TickBounds,
+};
+use crate::ticks::{max_usable_tick, min_usable_tick};
+use crate::types::{MigrationData, PoolKey};
+use alloy_primitives::aliases::I24;
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::SolValue;
+
+/// `Actions.MINT_POSITION` of v4-periphery
+pub const MINT_POSITION: u8 = 0x02;
+/// `Actions.SETTLE` of v4-periphery
+pub const SETTLE: u8 = 0x0b;
+/// `Actions.TAKE_PAIR` of v4-periphery
+pub const TAKE_PAIR: u8 = 0x11;
+/// `ActionConstants.CONTRACT_BALANCE`, settles the whole balance of the position manager
+pub const CONTRACT_BALANCE: U256 = U256::from_limbs([0, 0, 0, 1 << 63]);
+
+/// `Plan`: the actions and their parameters passed to `PositionManager.modifyLiquidities`.
+/// The builder methods append in the same order as `ActionsBuilder` and `ParamsBuilder`, and the `plan_*` ones
+/// mirror `StrategyPlanner`. Unlike `DynamicArray`, the parameters are not limited to `MAX_PARAMS_SIZE`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Plan {
+    pub actions: Vec<u8>,
+    pub params: Vec<Bytes>,
+}
+
+impl Plan {
+    /// `StrategyPlanner.init`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `StrategyPlanner.encode`: `abi.encode(actions, params)`
+    pub fn encode(&self) -> Vec<u8> {
+        (Bytes::copy_from_slice(&self.actions), self.params.clone()).abi_encode_params()
+    }
+
+    /// Decodes the `unlockData` of a `modifyLiquidities` call, to compare it with the plan of a migration
+    pub fn decode(data: &[u8]) -> alloy_sol_types::Result<Self> {
+        let (actions, params) = <(Bytes, Vec<Bytes>)>::abi_decode_params(data, true)?;
+        Ok(Self { actions: actions.to_vec(), params })
+    }
+
+    /// `addMint` with the parameters of `MINT_POSITION`, without hook data
+    pub fn add_mint(
+        mut self,
+        pool_key: &PoolKey,
+        bounds: TickBounds,
+        liquidity: u128,
+        amount0_max: u128,
+        amount1_max: u128,
+        owner: Address,
+    ) -> Self {
+        self.actions.push(MINT_POSITION);
+        self.params.push(
+            (
+                pool_key.clone(),
+                I24::unchecked_from(bounds.lower_tick),
+                I24::unchecked_from(bounds.upper_tick),
+                U256::from(liquidity),
+                U256::from(amount0_max),
+                U256::from(amount1_max),
+                owner,
+                Bytes::new(),
+            )
+                .abi_encode_params()
+                .into(),
+        );
+        self
+    }
+
+    /// `addSettle` with the parameters of `SETTLE`
+    pub fn add_settle(mut self, currency: Address, amount: U256, payer_is_user: bool) -> Self {
+        self.actions.push(SETTLE);
+        self.params.push((currency, amount, payer_is_user).abi_encode_params().into());
+        self
+    }
+
+    /// `addTakePair` with the parameters of `TAKE_PAIR`
+    pub fn add_take_pair(mut self, currency0: Address, currency1: Address, recipient: Address) -> Self {
+        self.actions.push(TAKE_PAIR);
+        self.params.push((currency0, currency1, recipient).abi_encode_params().into());
+        self
+    }
+
+    /// `StrategyPlanner.planFullRangePosition`: mints the full range position, then settles the whole balance of
+    /// the position manager in both currencies
+    pub fn plan_full_range_position(self, base: &BasePositionParams, params: FullRangeParams) -> Self {
+        let pool_key = base.pool_key();
+        let bounds = TickBounds {
+            lower_tick: min_usable_tick(base.pool_tick_spacing),
+            upper_tick: max_usable_tick(base.pool_tick_spacing),
+        };
+        let (amount0, amount1) = if base.currency_is_currency0() {
+            (params.currency_amount, params.token_amount)
+        } else {
+            (params.token_amount, params.currency_amount)
+        };
+        let (currency0, currency1) = (pool_key.currency0, pool_key.currency1);
+        self.add_mint(&pool_key, bounds, base.liquidity, amount0, amount1, base.position_recipient)
+            .add_settle(currency0, CONTRACT_BALANCE, false)
+            .add_settle(currency1, CONTRACT_BALANCE, false)
+    }
+
+    /// `StrategyPlanner.planOneSidedPosition`: mints the one-sided position, leaving the plan unchanged if it is
+    /// dropped. Fails where the Solidity reverts instead, if the liquidity overflows.
+    pub fn plan_one_sided_position(
+        self,
+        base: &BasePositionParams,
+        params: OneSidedParams,
+    ) -> Result<Self, MigrationError> {
+        let position = match plan_one_sided_position(base, params)? {
+            OneSidedPosition::Minted { position, .. } => position,
+            OneSidedPosition::Skipped(_) => return Ok(self),
+        };
+        let (amount0, amount1) =
+            if base.currency_is_currency0() == params.in_token { (0, params.amount) } else { (params.amount, 0) };
+        Ok(self.add_mint(
+            &base.pool_key(),
+            position.bounds,
+            position.liquidity,
+            amount0,
+            amount1,
+            base.position_recipient,
+        ))
+    }
+
+    /// `StrategyPlanner.planTakePair`: takes the open deltas of both currencies back to the strategy
+    pub fn plan_take_pair(self, base: &BasePositionParams) -> Self {
+        let (currency0, currency1) = base.currencies();
+        self.add_take_pair(currency0, currency1, base.hooks)
+    }
+}
+
+/// `FullRangeLBPStrategy._createPositionPlan`, shared by the governed strategies.
+/// `strategy_address` is the address the strategy is deployed to, the hooks of the pool.
+pub fn full_range_migration_plan(strategy: &LBPStrategy, data: &MigrationData, strategy_address: Address) -> Plan {
+    let base = strategy.base_position_params(data, strategy_address);
+    Plan::new()
+        .plan_full_range_position(&base, full_range_params(data))
+        .plan_take_pair(&base)
+}
+
+/// `AdvancedLBPStrategy._createPositionPlan` with the flags the strategy is deployed with
+pub fn advanced_migration_plan(
+    strategy: &LBPStrategy,
+    data: &MigrationData,
+    strategy_address: Address,
+    create_one_sided_token_position: bool,
+    create_one_sided_currency_position: bool,
+) -> Result<Plan, MigrationError> {
+    let base = strategy.base_position_params(data, strategy_address);
+    let mut plan = Plan::new().plan_full_range_position(&base, full_range_params(data));
+    if create_one_sided_token_position && strategy.reserve_token_amount > data.fullRangeTokenAmount {
+        let amount = strategy.reserve_token_amount - data.fullRangeTokenAmount;
+        plan = plan.plan_one_sided_position(&base, OneSidedParams { amount, in_token: true })?;
+    }
+    if create_one_sided_currency_position && data.leftoverCurrency > 0 {
+        plan = plan.plan_one_sided_position(&base, OneSidedParams { amount: data.leftoverCurrency, in_token: false })?;
+    }
+    Ok(plan.plan_take_pair(&base))
+}
+
+fn full_range_params(data: &MigrationData) -> FullRangeParams {
+    FullRangeParams { token_amount: data.fullRangeTokenAmount, currency_amount: data.fullRangeCurrencyAmount }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, hex, uint};
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+    const TOKEN: Address = address!("1111111111111111111111111111111111111111");
+    const STRATEGY: Address = address!("5555555555555555555555555555555555555555");
+
+    /// An advanced strategy with the currency as currency0, at 1:1 with 400 reserve tokens left for the one-sided
+    /// token position and no leftover currency
+    fn strategy() -> (LBPStrategy, MigrationData) {
+        let strategy = LBPStrategy {
+            pool_token: TOKEN,
+            currency: Address::ZERO,
+            total_supply: 1000 * E18,
+            reserve_token_amount: 500 * E18,
+            max_currency_amount_for_lp: u128::MAX,
+            pool_lp_fee: 3000,
+            pool_tick_spacing: 60,
+            position_recipient: address!("2222222222222222222222222222222222222222"),
+        };
+        let data = MigrationData {
+            sqrtPriceX96: uint!(79228162514264337593543950336_U160),
+            fullRangeTokenAmount: 100 * E18,
+            fullRangeCurrencyAmount: 100 * E18,
+            leftoverCurrency: 0,
+            liquidity: 100 * E18 + 5,
+        };
+        (strategy, data)
+    }
+
+    #[test]
+    fn constants_match_v4_periphery() {
+        assert_eq!([MINT_POSITION, SETTLE, TAKE_PAIR], [0x02, 0x0b, 0x11]);
+        assert_eq!(CONTRACT_BALANCE, uint!(0x8000000000000000000000000000000000000000000000000000000000000000_U256));
+    }
+
+    #[test]
+    fn full_range_plan_matches_abi_encode() {
+        // abi.encode(actions, params) passed to modifyLiquidities, the take pair going back to the strategy
+        let encoded = hex::decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            // actions
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "020b0b1100000000000000000000000000000000000000000000000000000000",
+            // params
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000240",
+            "00000000000000000000000000000000000000000000000000000000000002c0",
+            "0000000000000000000000000000000000000000000000000000000000000340",
+            // MINT_POSITION
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000000000000000000000000000000000000000000bb8",
+            "000000000000000000000000000000000000000000000000000000000000003c",
+            "0000000000000000000000005555555555555555555555555555555555555555",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffff2764c",
+            "00000000000000000000000000000000000000000000000000000000000d89b4",
+            "0000000000000000000000000000000000000000000000056bc75e2d63100005",
+            "0000000000000000000000000000000000000000000000056bc75e2d63100000",
+            "0000000000000000000000000000000000000000000000056bc75e2d63100000",
+            "0000000000000000000000002222222222222222222222222222222222222222",
+            "0000000000000000000000000000000000000000000000000000000000000180",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // SETTLE
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "8000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // SETTLE
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "8000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // TAKE_PAIR
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000005555555555555555555555555555555555555555",
+        ))
+        .unwrap();
+        let (strategy, data) = strategy();
+        let plan = full_range_migration_plan(&strategy, &data, STRATEGY);
+        assert_eq!(plan.actions, [MINT_POSITION, SETTLE, SETTLE, TAKE_PAIR]);
+        assert_eq!(plan.encode(), encoded);
+        assert_eq!(Plan::decode(&encoded), Ok(plan));
+    }
+
+    #[test]
+    fn one_sided_plan_matches_abi_encode() {
+        // The one-sided token position below the price is minted after settling the full range position
+        let encoded = hex::decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            // actions
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "020b0b0211000000000000000000000000000000000000000000000000000000",
+            // params
+            "0000000000000000000000000000000000000000000000000000000000000005",
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000260",
+            "00000000000000000000000000000000000000000000000000000000000002e0",
+            "0000000000000000000000000000000000000000000000000000000000000360",
+            "0000000000000000000000000000000000000000000000000000000000000520",
+            // MINT_POSITION
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000000000000000000000000000000000000000000bb8",
+            "000000000000000000000000000000000000000000000000000000000000003c",
+            "0000000000000000000000005555555555555555555555555555555555555555",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffff2764c",
+            "00000000000000000000000000000000000000000000000000000000000d89b4",
+            "0000000000000000000000000000000000000000000000056bc75e2d63100005",
+            "0000000000000000000000000000000000000000000000056bc75e2d63100000",
+            "0000000000000000000000000000000000000000000000056bc75e2d63100000",
+            "0000000000000000000000002222222222222222222222222222222222222222",
+            "0000000000000000000000000000000000000000000000000000000000000180",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // SETTLE
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "8000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // SETTLE
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "8000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // MINT_POSITION
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000000000000000000000000000000000000000000bb8",
+            "000000000000000000000000000000000000000000000000000000000000003c",
+            "0000000000000000000000005555555555555555555555555555555555555555",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffffff2764c",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000000000000000000000000015af1d78b58c400015",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "000000000000000000000000000000000000000000000015af1d78b58c400000",
+            "0000000000000000000000002222222222222222222222222222222222222222",
+            "0000000000000000000000000000000000000000000000000000000000000180",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            // TAKE_PAIR
+            "0000000000000000000000000000000000000000000000000000000000000060",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000001111111111111111111111111111111111111111",
+            "0000000000000000000000005555555555555555555555555555555555555555",
+        ))
+        .unwrap();
+        let (strategy, data) = strategy();
+        let plan = advanced_migration_plan(&strategy, &data, STRATEGY, true, true).unwrap();
+        assert_eq!(plan.actions, [MINT_POSITION, SETTLE, SETTLE, MINT_POSITION, TAKE_PAIR]);
+        assert_eq!(plan.encode(), encoded);
+        assert_eq!(Plan::decode(&encoded), Ok(plan));
+
+        // Without the one-sided positions, the plan is the one of the full range strategy
+        let plan = advanced_migration_plan(&strategy, &data, STRATEGY, false, false).unwrap();
+        assert_eq!(plan, full_range_migration_plan(&strategy, &data, STRATEGY));
+    }
+}
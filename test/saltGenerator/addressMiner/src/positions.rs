@@ -0,0 +1,429 @@
+use crate::migration::{LBPStrategy, MigrationError};
+use crate::ticks::{
+    get_liquidity_for_amounts, get_sqrt_price_at_tick, get_tick_at_sqrt_price, max_usable_tick, min_usable_tick,
+    tick_floor, tick_spacing_to_max_liquidity_per_tick, tick_strict_ceil, MAX_TICK, MIN_TICK,
+};
+use crate::types::{MigrationData, PoolKey};
+use alloy_primitives::aliases::{I24, U160, U24};
+use alloy_primitives::Address;
+use std::fmt;
+
+/// `BasePositionParams`: the pool and the full range position shared by the positions of a migration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BasePositionParams {
+    pub currency: Address,
+    pub pool_token: Address,
+    pub pool_lp_fee: u32,
+    pub pool_tick_spacing: i32,
+    pub initial_sqrt_price_x96: U160,
+    pub liquidity: u128,
+    pub position_recipient: Address,
+    /// The strategy, which is also the recipient of `planTakePair`
+    pub hooks: Address,
+}
+
+impl BasePositionParams {
+    /// `currency < poolToken`
+    pub fn currency_is_currency0(&self) -> bool {
+        self.currency < self.pool_token
+    }
+
+    /// The currencies sorted into `(currency0, currency1)`
+    pub fn currencies(&self) -> (Address, Address) {
+        if self.currency_is_currency0() {
+            (self.currency, self.pool_token)
+        } else {
+            (self.pool_token, self.currency)
+        }
+    }
+
+    /// The key of the pool the positions are minted in
+    pub fn pool_key(&self) -> PoolKey {
+        let (currency0, currency1) = self.currencies();
+        PoolKey {
+            currency0,
+            currency1,
+            fee: U24::from(self.pool_lp_fee),
+            tickSpacing: I24::unchecked_from(self.pool_tick_spacing),
+            hooks: self.hooks,
+        }
+    }
+}
+
+/// `FullRangeParams`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FullRangeParams {
+    pub token_amount: u128,
+    pub currency_amount: u128,
+}
+
+/// `TickBounds`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickBounds {
+    pub lower_tick: i32,
+    pub upper_tick: i32,
+}
+
+impl fmt::Display for TickBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lower_tick, self.upper_tick)
+    }
+}
+
+/// `OneSidedParams`: a position of only the token or only the currency
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OneSidedParams {
+    pub amount: u128,
+    pub in_token: bool,
+}
+
+/// A position minted by the migration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub bounds: TickBounds,
+    pub liquidity: u128,
+}
+
+/// Why a one-sided position is not minted.
+/// `StrategyPlanner.planOneSidedPosition` drops the position silently in the last four cases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The strategy is deployed without the position
+    Disabled,
+    /// No reserve tokens or currency are left after the full range position
+    NothingLeft,
+    /// Less than two tick spacings between the initial tick and `MIN_TICK`
+    TooCloseToMinTick { initial_tick: i32 },
+    /// Less than two tick spacings between the initial tick and `MAX_TICK`
+    TooCloseToMaxTick { initial_tick: i32 },
+    /// The amount is too small to provide any liquidity between the bounds
+    ZeroLiquidity { bounds: TickBounds },
+    /// The liquidity of both positions would exceed the max liquidity per tick of the tick spacing
+    ExceedsMaxLiquidityPerTick { bounds: TickBounds, liquidity: u128, max_liquidity_per_tick: u128 },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::Disabled => write!(f, "disabled"),
+            SkipReason::NothingLeft => write!(f, "nothing left after the full range position"),
+            SkipReason::TooCloseToMinTick { initial_tick } => {
+                write!(f, "initial tick {} is less than two tick spacings above MIN_TICK", initial_tick)
+            }
+            SkipReason::TooCloseToMaxTick { initial_tick } => {
+                write!(f, "initial tick {} is less than two tick spacings below MAX_TICK", initial_tick)
+            }
+            SkipReason::ZeroLiquidity { bounds } => write!(f, "no liquidity within ticks {}", bounds),
+            SkipReason::ExceedsMaxLiquidityPerTick { bounds, liquidity, max_liquidity_per_tick } => write!(
+                f,
+                "liquidity {} with the full range position exceeds the max liquidity per tick {} within ticks {}",
+                liquidity, max_liquidity_per_tick, bounds
+            ),
+        }
+    }
+}
+
+/// A one-sided position of the migration, either minted or dropped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OneSidedPosition {
+    Minted { position: Position, amount: u128 },
+    Skipped(SkipReason),
+}
+
+/// The positions `AdvancedLBPStrategy._createPositionPlan` mints and the amounts transferred to the position manager
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdvancedPositions {
+    pub full_range: Position,
+    pub one_sided_token: OneSidedPosition,
+    pub one_sided_currency: OneSidedPosition,
+    /// `_getTokenTransferAmount`, the tokens of a dropped one-sided position are swept back afterwards
+    pub token_transfer_amount: u128,
+    /// `_getCurrencyTransferAmount`
+    pub currency_transfer_amount: u128,
+}
+
+/// `StrategyPlanner.getLeftSideBounds`: from the lowest usable tick to the initial tick rounded down,
+/// `None` where it returns `(0, 0)` as the initial tick is too close to `MIN_TICK`
+pub fn left_side_bounds(initial_tick: i32, tick_spacing: i32) -> Option<TickBounds> {
+    if initial_tick - MIN_TICK < tick_spacing * 2 {
+        return None;
+    }
+    Some(TickBounds { lower_tick: min_usable_tick(tick_spacing), upper_tick: tick_floor(initial_tick, tick_spacing) })
+}
+
+/// `StrategyPlanner.getRightSideBounds`: from above the initial tick to the highest usable tick,
+/// `None` where it returns `(0, 0)` as the initial tick is too close to `MAX_TICK`
+pub fn right_side_bounds(initial_tick: i32, tick_spacing: i32) -> Option<TickBounds> {
+    if MAX_TICK - initial_tick < tick_spacing * 2 {
+        return None;
+    }
+    Some(TickBounds {
+        lower_tick: tick_strict_ceil(initial_tick, tick_spacing),
+        upper_tick: max_usable_tick(tick_spacing),
+    })
+}
+
+/// `StrategyPlanner.planOneSidedPosition`: the one-sided position minted next to the full range position of the
+/// migration, or why it is dropped. Fails where the Solidity reverts instead, if the liquidity overflows.
+pub fn plan_one_sided_position(
+    base: &BasePositionParams,
+    params: OneSidedParams,
+) -> Result<OneSidedPosition, MigrationError> {
+    let currency_is_currency0 = base.currency_is_currency0();
+    let tick_spacing = base.pool_tick_spacing;
+    let initial_tick = get_tick_at_sqrt_price(base.initial_sqrt_price_x96)?;
+
+    // A position below the price only holds currency1, so it is on the left if the amount is in currency1
+    let below_price = currency_is_currency0 == params.in_token;
+    let bounds = if below_price {
+        left_side_bounds(initial_tick, tick_spacing).ok_or(SkipReason::TooCloseToMinTick { initial_tick })
+    } else {
+        right_side_bounds(initial_tick, tick_spacing).ok_or(SkipReason::TooCloseToMaxTick { initial_tick })
+    };
+    let bounds = match bounds {
+        Ok(bounds) => bounds,
+        Err(reason) => return Ok(OneSidedPosition::Skipped(reason)),
+    };
+
+    let (amount0, amount1) = if below_price { (0, params.amount) } else { (params.amount, 0) };
+    let liquidity = get_liquidity_for_amounts(
+        base.initial_sqrt_price_x96,
+        get_sqrt_price_at_tick(bounds.lower_tick)?,
+        get_sqrt_price_at_tick(bounds.upper_tick)?,
+        amount0,
+        amount1,
+    )?;
+    if liquidity == 0 {
+        return Ok(OneSidedPosition::Skipped(SkipReason::ZeroLiquidity { bounds }));
+    }
+
+    let total_liquidity = base.liquidity.checked_add(liquidity).ok_or(MigrationError::ArithmeticOverflow)?;
+    let max_liquidity_per_tick = tick_spacing_to_max_liquidity_per_tick(tick_spacing);
+    if total_liquidity > max_liquidity_per_tick {
+        return Ok(OneSidedPosition::Skipped(SkipReason::ExceedsMaxLiquidityPerTick {
+            bounds,
+            liquidity: total_liquidity,
+            max_liquidity_per_tick,
+        }));
+    }
+
+    Ok(OneSidedPosition::Minted { position: Position { bounds, liquidity }, amount: params.amount })
+}
+
+/// Mirrors `AdvancedLBPStrategy._createPositionPlan` with the flags the strategy is deployed with
+pub fn plan_advanced_positions(
+    strategy: &LBPStrategy,
+    data: &MigrationData,
+    create_one_sided_token_position: bool,
+    create_one_sided_currency_position: bool,
+) -> Result<AdvancedPositions, MigrationError> {
+    // The positions do not depend on the hooks of the pool
+    let base = strategy.base_position_params(data, Address::ZERO);
+    let full_range = Position {
+        bounds: TickBounds {
+            lower_tick: min_usable_tick(strategy.pool_tick_spacing),
+            upper_tick: max_usable_tick(strategy.pool_tick_spacing),
+        },
+        liquidity: data.liquidity,
+    };
+
+    // `calculateAmounts` never returns more tokens than the reserve
+    let token_left = strategy.reserve_token_amount - data.fullRangeTokenAmount;
+    let one_sided_token = match (create_one_sided_token_position, token_left) {
+        (false, _) => OneSidedPosition::Skipped(SkipReason::Disabled),
+        (true, 0) => OneSidedPosition::Skipped(SkipReason::NothingLeft),
+        (true, amount) => plan_one_sided_position(&base, OneSidedParams { amount, in_token: true })?,
+    };
+    let one_sided_currency = match (create_one_sided_currency_position, data.leftoverCurrency) {
+        (false, _) => OneSidedPosition::Skipped(SkipReason::Disabled),
+        (true, 0) => OneSidedPosition::Skipped(SkipReason::NothingLeft),
+        (true, amount) => plan_one_sided_position(&base, OneSidedParams { amount, in_token: false })?,
+    };
+
+    let token_transfer_amount = if create_one_sided_token_position && token_left > 0 {
+        strategy.reserve_token_amount
+    } else {
+        data.fullRangeTokenAmount
+    };
+    let currency_transfer_amount = if create_one_sided_currency_position && data.leftoverCurrency > 0 {
+        data.fullRangeCurrencyAmount.checked_add(data.leftoverCurrency).ok_or(MigrationError::ArithmeticOverflow)?
+    } else {
+        data.fullRangeCurrencyAmount
+    };
+
+    Ok(AdvancedPositions {
+        full_range,
+        one_sided_token,
+        one_sided_currency,
+        token_transfer_amount,
+        currency_transfer_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::{MAX_SQRT_PRICE, MIN_SQRT_PRICE, Q96};
+    use alloy_primitives::{address, uint, U256};
+
+    const E18: u128 = 1_000_000_000_000_000_000;
+    const TOKEN: Address = address!("1111111111111111111111111111111111111111");
+    /// Sorted after the token, so the currency is currency1
+    const CURRENCY1: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+    /// 1:1, at tick 0
+    const SQRT_PRICE_1_1: U160 = uint!(79228162514264337593543950336_U160);
+    /// Two of currency1 per token, at tick 6931
+    const SQRT_PRICE_2_1: U160 = uint!(112045541949572279837463876454_U160);
+
+    fn base(currency: Address, initial_sqrt_price_x96: U160, liquidity: u128) -> BasePositionParams {
+        BasePositionParams {
+            currency,
+            pool_token: TOKEN,
+            pool_lp_fee: 3000,
+            pool_tick_spacing: 60,
+            initial_sqrt_price_x96,
+            liquidity,
+            position_recipient: address!("2222222222222222222222222222222222222222"),
+            hooks: address!("5555555555555555555555555555555555555555"),
+        }
+    }
+
+    fn minted(lower_tick: i32, upper_tick: i32, liquidity: u128, amount: u128) -> OneSidedPosition {
+        let bounds = TickBounds { lower_tick, upper_tick };
+        OneSidedPosition::Minted { position: Position { bounds, liquidity }, amount }
+    }
+
+    #[test]
+    fn side_bounds_need_two_tick_spacings_to_the_tick_limits() {
+        assert_eq!(left_side_bounds(0, 60), Some(TickBounds { lower_tick: -887220, upper_tick: 0 }));
+        assert_eq!(left_side_bounds(-1, 60), Some(TickBounds { lower_tick: -887220, upper_tick: -60 }));
+        assert_eq!(left_side_bounds(MIN_TICK + 120, 60), Some(TickBounds { lower_tick: -887220, upper_tick: -887160 }));
+        assert_eq!(left_side_bounds(MIN_TICK + 119, 60), None);
+
+        // Strictly above the initial tick, which is in the full range position only
+        assert_eq!(right_side_bounds(0, 60), Some(TickBounds { lower_tick: 60, upper_tick: 887220 }));
+        assert_eq!(right_side_bounds(60, 60), Some(TickBounds { lower_tick: 120, upper_tick: 887220 }));
+        assert_eq!(right_side_bounds(MAX_TICK - 120, 60), Some(TickBounds { lower_tick: 887160, upper_tick: 887220 }));
+        assert_eq!(right_side_bounds(MAX_TICK - 119, 60), None);
+    }
+
+    #[test]
+    fn one_sided_position_is_on_the_side_of_its_currency() {
+        // The token is currency1 and goes below the price, the currency above it
+        let currency0 = base(Address::ZERO, SQRT_PRICE_1_1, 100 * E18);
+        let token = OneSidedParams { amount: 400 * E18, in_token: true };
+        let currency = OneSidedParams { amount: 20 * E18, in_token: false };
+        assert_eq!(
+            plan_one_sided_position(&currency0, token),
+            Ok(minted(-887220, 0, 400000000000000000021, 400 * E18))
+        );
+        assert_eq!(
+            plan_one_sided_position(&currency0, currency),
+            Ok(minted(60, 887220, 20060087081254838514, 20 * E18))
+        );
+
+        // The token is currency0 and goes above the price, the currency below it
+        let token = OneSidedParams { amount: 7 * E18, in_token: true };
+        let currency1 = base(CURRENCY1, SQRT_PRICE_2_1, 100 * E18);
+        assert_eq!(plan_one_sided_position(&currency1, token), Ok(minted(6960, 887220, 9913453264091605661, 7 * E18)));
+        assert_eq!(
+            plan_one_sided_position(&currency1, currency),
+            Ok(minted(-887220, 6900, 14164651391197228662, 20 * E18))
+        );
+    }
+
+    #[test]
+    fn one_sided_position_is_dropped_like_the_strategy_planner() {
+        let token = OneSidedParams { amount: E18, in_token: true };
+        let currency = OneSidedParams { amount: E18, in_token: false };
+        assert_eq!(
+            plan_one_sided_position(&base(Address::ZERO, MIN_SQRT_PRICE, E18), token),
+            Ok(OneSidedPosition::Skipped(SkipReason::TooCloseToMinTick { initial_tick: MIN_TICK }))
+        );
+        assert_eq!(
+            plan_one_sided_position(&base(Address::ZERO, MAX_SQRT_PRICE - U160::from(1), E18), currency),
+            Ok(OneSidedPosition::Skipped(SkipReason::TooCloseToMaxTick { initial_tick: MAX_TICK - 1 }))
+        );
+
+        // A single wei of currency0 far below the upper tick
+        let low_price = base(Address::ZERO, U160::from(Q96 / U256::from(1000)), E18);
+        assert_eq!(
+            plan_one_sided_position(&low_price, OneSidedParams { amount: 1, in_token: false }),
+            Ok(OneSidedPosition::Skipped(SkipReason::ZeroLiquidity {
+                bounds: TickBounds { lower_tick: -138120, upper_tick: 887220 }
+            }))
+        );
+
+        let max_liquidity_per_tick = tick_spacing_to_max_liquidity_per_tick(60);
+        let full = base(Address::ZERO, SQRT_PRICE_1_1, max_liquidity_per_tick);
+        assert_eq!(
+            plan_one_sided_position(&full, currency),
+            Ok(OneSidedPosition::Skipped(SkipReason::ExceedsMaxLiquidityPerTick {
+                bounds: TickBounds { lower_tick: 60, upper_tick: 887220 },
+                liquidity: max_liquidity_per_tick + 1003004354062741925,
+                max_liquidity_per_tick,
+            }))
+        );
+        assert_eq!(
+            plan_one_sided_position(&base(Address::ZERO, SQRT_PRICE_1_1, u128::MAX), currency),
+            Err(MigrationError::ArithmeticOverflow)
+        );
+    }
+
+    #[test]
+    fn advanced_positions_mint_what_is_left_after_the_full_range_position() {
+        let strategy = LBPStrategy {
+            pool_token: TOKEN,
+            currency: Address::ZERO,
+            total_supply: 1000 * E18,
+            reserve_token_amount: 500 * E18,
+            max_currency_amount_for_lp: u128::MAX,
+            pool_lp_fee: 3000,
+            pool_tick_spacing: 60,
+            position_recipient: address!("2222222222222222222222222222222222222222"),
+        };
+        let data = MigrationData {
+            sqrtPriceX96: SQRT_PRICE_1_1,
+            fullRangeTokenAmount: 100 * E18,
+            fullRangeCurrencyAmount: 100 * E18,
+            leftoverCurrency: 0,
+            liquidity: 100 * E18 + 5,
+        };
+        let full_range =
+            Position { bounds: TickBounds { lower_tick: -887220, upper_tick: 887220 }, liquidity: data.liquidity };
+        assert_eq!(
+            plan_advanced_positions(&strategy, &data, true, true),
+            Ok(AdvancedPositions {
+                full_range,
+                one_sided_token: minted(-887220, 0, 400000000000000000021, 400 * E18),
+                one_sided_currency: OneSidedPosition::Skipped(SkipReason::NothingLeft),
+                token_transfer_amount: 500 * E18,
+                currency_transfer_amount: 100 * E18,
+            })
+        );
+        assert_eq!(
+            plan_advanced_positions(&strategy, &data, false, false),
+            Ok(AdvancedPositions {
+                full_range,
+                one_sided_token: OneSidedPosition::Skipped(SkipReason::Disabled),
+                one_sided_currency: OneSidedPosition::Skipped(SkipReason::Disabled),
+                token_transfer_amount: 100 * E18,
+                currency_transfer_amount: 100 * E18,
+            })
+        );
+
+        // The currency left once the reserve is used up
+        let strategy = LBPStrategy { currency: CURRENCY1, reserve_token_amount: 30 * E18, ..strategy };
+        let data = MigrationData {
+            sqrtPriceX96: SQRT_PRICE_2_1,
+            fullRangeTokenAmount: 30 * E18,
+            fullRangeCurrencyAmount: 60 * E18,
+            leftoverCurrency: 20 * E18,
+            liquidity: 42426406871192851465,
+        };
+        let positions = plan_advanced_positions(&strategy, &data, true, true).unwrap();
+        assert_eq!(positions.one_sided_token, OneSidedPosition::Skipped(SkipReason::NothingLeft));
+        assert_eq!(positions.one_sided_currency, minted(-887220, 6900, 14164651391197228662, 20 * E18));
+        assert_eq!((positions.token_transfer_amount, positions.currency_transfer_amount), (30 * E18, 80 * E18));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::math::{mul_div, sqrt};
+use alloy_primitives::{uint, U160, U256};
+use std::fmt;
+
+/// `FixedPoint96.RESOLUTION`
+pub const RESOLUTION: usize = 96;
+/// `FixedPoint96.Q96`
+pub const Q96: U256 = uint!(0x1000000000000000000000000_U256);
+/// `TokenPricing.Q192`
+pub const Q192: U256 = uint!(0x1000000000000000000000000000000000000000000000000_U256);
+/// `TickMath.MIN_SQRT_PRICE`
+pub const MIN_SQRT_PRICE: U160 = uint!(4295128739_U160);
+/// `TickMath.MAX_SQRT_PRICE`
+pub const MAX_SQRT_PRICE: U160 = uint!(1461446703485210103287273052203988822378723970342_U160);
+
+/// The reverts of `TokenPricing`, with the same parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PricingError {
+    PriceIsZero { price: U256 },
+    PriceTooHigh { price: U256, max_price: U256 },
+    SqrtPriceX96OutOfBounds { sqrt_price_x96: U160, min_sqrt_price_x96: U160, max_sqrt_price_x96: U160 },
+    AmountOverflow { currency_amount: U256 },
+    /// `FullMath.mulDiv` reverts without a reason when the result overflows or the denominator is zero
+    MulDivOverflow,
+}
+
+impl fmt::Display for PricingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PricingError::PriceIsZero { price } => write!(f, "PriceIsZero({})", price),
+            PricingError::PriceTooHigh { price, max_price } => write!(f, "PriceTooHigh({}, {})", price, max_price),
+            PricingError::SqrtPriceX96OutOfBounds { sqrt_price_x96, min_sqrt_price_x96, max_sqrt_price_x96 } => {
+                write!(f, "SqrtPriceX96OutOfBounds({}, {}, {})", sqrt_price_x96, min_sqrt_price_x96, max_sqrt_price_x96)
+            }
+            PricingError::AmountOverflow { currency_amount } => write!(f, "AmountOverflow({})", currency_amount),
+            PricingError::MulDivOverflow => write!(f, "FullMath.mulDiv overflow"),
+        }
+    }
+}
+
+impl std::error::Error for PricingError {}
+
+/// `TokenPricing.convertToPriceX192`: converts a Q96 price of the token in currency to an X192 price of
+/// currency1 in currency0, inverting it if the currency is currency0
+pub fn convert_to_price_x192(price: U256, currency_is_currency0: bool) -> Result<U256, PricingError> {
+    if price.is_zero() {
+        return Err(PricingError::PriceIsZero { price });
+    }
+    let max_price = U256::from(U160::MAX);
+
+    if currency_is_currency0 {
+        let inverted = Q192 / price;
+        if inverted >> 160 != U256::ZERO {
+            return Err(PricingError::PriceTooHigh { price: inverted, max_price });
+        }
+        mul_div(Q192, Q96, price).ok_or(PricingError::MulDivOverflow)
+    } else {
+        if price >> 160 != U256::ZERO {
+            return Err(PricingError::PriceTooHigh { price, max_price });
+        }
+        Ok(price << RESOLUTION)
+    }
+}
+
+/// `TokenPricing.convertToSqrtPriceX96`: the square root of an X192 price rounded down, within the bounds of `TickMath`
+pub fn convert_to_sqrt_price_x96(price_x192: U256) -> Result<U160, PricingError> {
+    // The square root of a 256-bit number always fits in 128 bits
+    let sqrt_price_x96 = U160::from(sqrt(price_x192));
+    if sqrt_price_x96 < MIN_SQRT_PRICE || sqrt_price_x96 > MAX_SQRT_PRICE {
+        return Err(PricingError::SqrtPriceX96OutOfBounds {
+            sqrt_price_x96,
+            min_sqrt_price_x96: MIN_SQRT_PRICE,
+            max_sqrt_price_x96: MAX_SQRT_PRICE,
+        });
+    }
+    Ok(sqrt_price_x96)
+}
+
+/// `TokenPricing.calculateAmounts`: returns the token amount worth the currency amount at the X192 price and the
+/// currency amount, both reduced to the currency worth the reserve if the token amount would exceed it
+pub fn calculate_amounts(
+    price_x192: U256,
+    currency_amount: u128,
+    currency_is_currency0: bool,
+    reserve_token_amount: u128,
+) -> Result<(u128, u128), PricingError> {
+    let token_amount = if currency_is_currency0 {
+        mul_div(price_x192, U256::from(currency_amount), Q192)
+    } else {
+        mul_div(U256::from(currency_amount), Q192, price_x192)
+    }
+    .ok_or(PricingError::MulDivOverflow)?;
+
+    if token_amount <= U256::from(reserve_token_amount) {
+        // At most the reserve, which fits in 128 bits
+        return Ok((token_amount.to::<u128>(), currency_amount));
+    }
+
+    let corresponding_currency_amount = if currency_is_currency0 {
+        mul_div(U256::from(reserve_token_amount), Q192, price_x192)
+    } else {
+        mul_div(price_x192, U256::from(reserve_token_amount), Q192)
+    }
+    .ok_or(PricingError::MulDivOverflow)?;
+    if corresponding_currency_amount > U256::from(u128::MAX) {
+        return Err(PricingError::AmountOverflow { currency_amount: corresponding_currency_amount });
+    }
+    Ok((reserve_token_amount, corresponding_currency_amount.to::<u128>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const E18: U256 = uint!(1000000000000000000_U256);
+
+    #[test]
+    fn convert_to_price_x192_matches_token_pricing() {
+        assert_eq!(convert_to_price_x192(E18, true), Ok(mul_div(Q192, Q96, E18).unwrap()));
+        assert_eq!(convert_to_price_x192(E18, false), Ok(E18 << 96));
+        // The prices closest to the limit on either side
+        let max_price = U256::from(U160::MAX);
+        let lowest_price = U256::from(1u64 << 32) + U256::from(1);
+        assert_eq!(convert_to_price_x192(lowest_price, true), Ok(mul_div(Q192, Q96, lowest_price).unwrap()));
+        assert_eq!(convert_to_price_x192(max_price, false), Ok(max_price << 96));
+    }
+
+    #[test]
+    fn convert_to_price_x192_reverts_like_token_pricing() {
+        let max_price = U256::from(U160::MAX);
+        assert_eq!(convert_to_price_x192(U256::ZERO, true), Err(PricingError::PriceIsZero { price: U256::ZERO }));
+        assert_eq!(convert_to_price_x192(U256::ZERO, false), Err(PricingError::PriceIsZero { price: U256::ZERO }));
+        // Inverted, the price of currency1 in currency0 is `Q192 / price`
+        assert_eq!(
+            convert_to_price_x192(U256::from(1u64 << 32), true),
+            Err(PricingError::PriceTooHigh { price: U256::from(1) << 160, max_price })
+        );
+        assert_eq!(
+            convert_to_price_x192(U256::from(1), true),
+            Err(PricingError::PriceTooHigh { price: Q192, max_price })
+        );
+        assert_eq!(
+            convert_to_price_x192(max_price + U256::from(1), false),
+            Err(PricingError::PriceTooHigh { price: max_price + U256::from(1), max_price })
+        );
+    }
+
+    #[test]
+    fn convert_to_sqrt_price_x96_matches_token_pricing() {
+        let price_x192 = |numerator: u64, denominator: u64| {
+            mul_div(E18 * U256::from(numerator), Q192, E18 * U256::from(denominator)).unwrap()
+        };
+        assert_eq!(convert_to_sqrt_price_x96(price_x192(1, 1)), Ok(uint!(79228162514264337593543950336_U160)));
+        assert_eq!(convert_to_sqrt_price_x96(price_x192(100, 1)), Ok(uint!(792281625142643375935439503360_U160)));
+        assert_eq!(convert_to_sqrt_price_x96(price_x192(1, 100)), Ok(uint!(7922816251426433759354395033_U160)));
+        assert_eq!(convert_to_sqrt_price_x96(price_x192(111, 333)), Ok(uint!(45742400955009932534161870629_U160)));
+        assert_eq!(convert_to_sqrt_price_x96(price_x192(333, 111)), Ok(uint!(137227202865029797602485611888_U160)));
+    }
+
+    #[test]
+    fn convert_to_sqrt_price_x96_rounds_down_within_the_bounds() {
+        assert_eq!(convert_to_sqrt_price_x96(Q192 - U256::from(1)), Ok(U160::from(Q96) - U160::from(1)));
+
+        let min_price_x192 = U256::from(MIN_SQRT_PRICE) * U256::from(MIN_SQRT_PRICE);
+        assert_eq!(convert_to_sqrt_price_x96(min_price_x192), Ok(MIN_SQRT_PRICE));
+        let out_of_bounds = |sqrt_price_x96: U160| PricingError::SqrtPriceX96OutOfBounds {
+            sqrt_price_x96,
+            min_sqrt_price_x96: MIN_SQRT_PRICE,
+            max_sqrt_price_x96: MAX_SQRT_PRICE,
+        };
+        assert_eq!(
+            convert_to_sqrt_price_x96(min_price_x192 - U256::from(1)),
+            Err(out_of_bounds(MIN_SQRT_PRICE - U160::from(1)))
+        );
+        assert_eq!(convert_to_sqrt_price_x96(U256::ZERO), Err(out_of_bounds(U160::ZERO)));
+    }
+
+    #[test]
+    fn calculate_amounts_is_limited_by_the_reserve() {
+        // 1:1, the whole currency amount while the reserve covers it
+        assert_eq!(calculate_amounts(Q192, 100, true, 1000), Ok((100, 100)));
+        assert_eq!(calculate_amounts(Q192, 100, false, 100), Ok((100, 100)));
+        assert_eq!(calculate_amounts(Q192, 100, false, 40), Ok((40, 40)));
+
+        // Three of currency1 per currency0, rounded down on both sides
+        let price_x192 = Q192 * U256::from(3);
+        assert_eq!(calculate_amounts(price_x192, 10, true, 1000), Ok((30, 10)));
+        assert_eq!(calculate_amounts(price_x192, 10, false, 1000), Ok((3, 10)));
+        assert_eq!(calculate_amounts(price_x192, 10, true, 20), Ok((20, 6)));
+        assert_eq!(calculate_amounts(price_x192, 10, false, 2), Ok((2, 6)));
+    }
+
+    #[test]
+    fn calculate_amounts_reverts_where_mul_div_does() {
+        // Divides by the price, or overflows 256 bits with the lowest price
+        assert_eq!(calculate_amounts(U256::ZERO, 100, false, 1000), Err(PricingError::MulDivOverflow));
+        assert_eq!(calculate_amounts(U256::from(1), u128::MAX, false, 0), Err(PricingError::MulDivOverflow));
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::time::Duration;
+
+/// The progress of a search after some time, displayed as a status line
+#[derive(Clone, Copy, Debug)]
+pub struct Progress {
+    pub attempts: u64,
+    pub elapsed: Duration,
+    /// Probability of an attempt fulfilling the requirements, unknown with a regex
+    pub probability: Option<f64>,
+}
+
+impl Progress {
+    /// Returns the number of attempts per second
+    pub fn hashrate(&self) -> f64 {
+        self.attempts as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Returns the probability of having found a match within the attempts so far, `1 - (1 - p)^attempts`
+    pub fn luck(&self) -> Option<f64> {
+        self.probability.map(|probability| -((-probability).ln_1p() * self.attempts as f64).exp_m1())
+    }
+
+    /// Returns the expected time until the next match at the current hashrate.
+    /// Attempts are independent, so it does not decrease with the attempts already made.
+    pub fn eta(&self) -> Option<Duration> {
+        let probability = self.probability.filter(|probability| *probability > 0.0)?;
+        if self.attempts == 0 {
+            return None;
+        }
+        // Clamped as `Duration::from_secs_f64` panics on overflow, which is far beyond any meaningful ETA
+        Some(Duration::from_secs_f64((1.0 / probability / self.hashrate()).min(1e15)))
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} attempts, {}/s", format_count(self.attempts as f64), format_count(self.hashrate()))?;
+        if let Some(luck) = self.luck() {
+            write!(f, ", {:.0}% chance of a match by now", luck * 100.0)?;
+        }
+        if let Some(eta) = self.eta() {
+            write!(f, ", ETA ~{}", format_duration(eta))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a count with a metric suffix, e.g. `1.25M`
+pub fn format_count(count: f64) -> String {
+    const SUFFIXES: [(f64, &str); 4] = [(1e12, "T"), (1e9, "G"), (1e6, "M"), (1e3, "k")];
+    match SUFFIXES.iter().find(|(scale, _)| count >= *scale) {
+        Some((scale, suffix)) => format!("{:.2}{}", count / scale, suffix),
+        None => format!("{:.0}", count),
+    }
+}
+
+/// Formats a duration with its two most significant units, e.g. `3h 25m`
+pub fn format_duration(duration: Duration) -> String {
+    const UNITS: [(u64, &str); 5] = [(365 * 86400, "y"), (86400, "d"), (3600, "h"), (60, "m"), (1, "s")];
+    let seconds = duration.as_secs();
+    let Some(first) = UNITS.iter().position(|(unit, _)| seconds >= *unit) else {
+        return "0s".to_string();
+    };
+    let (unit, suffix) = UNITS[first];
+    let mut formatted = format!("{}{}", seconds / unit, suffix);
+    if let Some((next_unit, next_suffix)) = UNITS.get(first + 1) {
+        let count = seconds % unit / next_unit;
+        if count > 0 {
+            formatted.push_str(&format!(" {}{}", count, next_suffix));
+        }
+    }
+    formatted
+}
@@ -0,0 +1,80 @@
+use alloy_primitives::Address;
+use clap::ValueEnum;
+use std::fmt;
+
+/// How the scoring mode ranks the strategy addresses fulfilling the requirements.
+/// Zero bytes in an address are cheaper in calldata, which matters as the strategy is the hook in every `PoolKey`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScoreObjective {
+    ZeroBytes,
+    ZeroNibbles,
+}
+
+impl ScoreObjective {
+    /// Returns the score of an address, higher is better
+    pub fn score(self, address: Address) -> u32 {
+        let zero_bytes = address.iter().take_while(|byte| **byte == 0).count() as u32;
+        match self {
+            ScoreObjective::ZeroBytes => zero_bytes,
+            ScoreObjective::ZeroNibbles => {
+                let next_nibble_is_zero = address.get(zero_bytes as usize).is_some_and(|byte| byte >> 4 == 0);
+                zero_bytes * 2 + next_nibble_is_zero as u32
+            }
+        }
+    }
+}
+
+impl fmt::Display for ScoreObjective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoreObjective::ZeroBytes => write!(f, "leading zero bytes"),
+            ScoreObjective::ZeroNibbles => write!(f, "leading zero nibbles"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn zero_bytes_counts_the_leading_zero_bytes() {
+        let objective = ScoreObjective::ZeroBytes;
+        assert_eq!(objective.score(address!("ffffffffffffffffffffffffffffffffffffffff")), 0);
+        assert_eq!(objective.score(address!("0fffffffffffffffffffffffffffffffffffffff")), 0);
+        assert_eq!(objective.score(address!("00ffffffffffffffffffffffffffffffffffffff")), 1);
+        assert_eq!(objective.score(address!("0000000fffffffffffffffffffffffffffffffff")), 3);
+        // Zero bytes after the first nonzero one do not count
+        assert_eq!(objective.score(address!("00ff000000000000000000000000000000000000")), 1);
+        assert_eq!(objective.score(Address::ZERO), 20);
+    }
+
+    #[test]
+    fn zero_nibbles_counts_the_leading_zero_nibbles() {
+        let objective = ScoreObjective::ZeroNibbles;
+        assert_eq!(objective.score(address!("ffffffffffffffffffffffffffffffffffffffff")), 0);
+        assert_eq!(objective.score(address!("0fffffffffffffffffffffffffffffffffffffff")), 1);
+        assert_eq!(objective.score(address!("00ffffffffffffffffffffffffffffffffffffff")), 2);
+        assert_eq!(objective.score(address!("0000000fffffffffffffffffffffffffffffffff")), 7);
+        assert_eq!(objective.score(address!("00f0000000000000000000000000000000000000")), 2);
+        assert_eq!(objective.score(Address::ZERO), 40);
+    }
+
+    #[test]
+    fn more_leading_zeros_score_higher() {
+        let addresses = [
+            address!("1000000000000000000000000000000000000000"),
+            address!("0100000000000000000000000000000000000000"),
+            address!("0010000000000000000000000000000000000000"),
+            address!("0001000000000000000000000000000000000000"),
+            address!("0000100000000000000000000000000000000000"),
+        ];
+        for pair in addresses.windows(2) {
+            let nibbles = ScoreObjective::ZeroNibbles;
+            assert!(nibbles.score(pair[0]) < nibbles.score(pair[1]), "{} < {}", pair[0], pair[1]);
+            let bytes = ScoreObjective::ZeroBytes;
+            assert!(bytes.score(pair[0]) <= bytes.score(pair[1]), "{} <= {}", pair[0], pair[1]);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_sol_types::SolValue;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::hooks::HookPermissions;
+use crate::types::{AdvancedConfigData, ConfigData, FullRangeConfigData, GovernedConfigData, MigratorParameters};
+
+/// The LBP strategies deployed by the strategy factories
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    FullRange,
+    Advanced,
+    Governed,
+    VirtualGoverned,
+}
+
+impl StrategyKind {
+    /// Returns the hook permissions the strategy is deployed with
+    /// Mirrors SelfInitializerHook.getHookPermissions and its override in GovernedLBPStrategy
+    pub const fn hook_permissions(self) -> HookPermissions {
+        match self {
+            StrategyKind::FullRange | StrategyKind::Advanced => {
+                HookPermissions { before_initialize: true, ..HOOK_PERMISSIONS_NONE }
+            }
+            StrategyKind::Governed | StrategyKind::VirtualGoverned => {
+                HookPermissions { before_initialize: true, before_swap: true, ..HOOK_PERMISSIONS_NONE }
+            }
+        }
+    }
+
+    /// Returns the name of the strategy contract
+    pub const fn contract_name(self) -> &'static str {
+        match self {
+            StrategyKind::FullRange => "FullRangeLBPStrategy",
+            StrategyKind::Advanced => "AdvancedLBPStrategy",
+            StrategyKind::Governed => "GovernedLBPStrategy",
+            StrategyKind::VirtualGoverned => "VirtualGovernedLBPStrategy",
+        }
+    }
+
+    /// Returns the ABI-encoded constructor arguments the factory deploys the strategy with
+    /// Mirrors `_validateParamsAndReturnDeployedBytecode` of the strategy factories
+    pub fn constructor_args(self, params: &LaunchParams) -> Result<Vec<u8>, StrategyError> {
+        if params.total_supply > U256::from(u128::MAX) {
+            return Err(StrategyError::InvalidAmount(params.total_supply, u128::MAX));
+        }
+        let total_supply = params.total_supply.to::<u128>();
+
+        let args = match self {
+            StrategyKind::FullRange => {
+                let config = FullRangeConfigData::abi_decode_config(&params.config_data)?;
+                (
+                    params.token,
+                    total_supply,
+                    config.migratorParams,
+                    config.auctionParams,
+                    params.position_manager,
+                    params.pool_manager,
+                )
+                    .abi_encode_params()
+            }
+            StrategyKind::Advanced => {
+                let config = AdvancedConfigData::abi_decode_config(&params.config_data)?;
+                (
+                    params.token,
+                    total_supply,
+                    config.migratorParams,
+                    config.auctionParams,
+                    params.position_manager,
+                    params.pool_manager,
+                    config.createOneSidedTokenPosition,
+                    config.createOneSidedCurrencyPosition,
+                )
+                    .abi_encode_params()
+            }
+            // The virtual governed strategy shares the constructor of the governed strategy
+            StrategyKind::Governed | StrategyKind::VirtualGoverned => {
+                let config = GovernedConfigData::abi_decode_config(&params.config_data)?;
+                (
+                    params.token,
+                    total_supply,
+                    config.migratorParams,
+                    config.auctionParams,
+                    params.position_manager,
+                    params.pool_manager,
+                    config.governanceAddress,
+                )
+                    .abi_encode_params()
+            }
+        };
+        Ok(args)
+    }
+
+    /// Returns the migrator parameters of the config data the factory deploys the strategy with
+    pub fn migrator_params(self, config_data: &[u8]) -> Result<MigratorParameters, StrategyError> {
+        let migrator_params = match self {
+            StrategyKind::FullRange => FullRangeConfigData::abi_decode_config(config_data)?.migratorParams,
+            StrategyKind::Advanced => AdvancedConfigData::abi_decode_config(config_data)?.migratorParams,
+            StrategyKind::Governed | StrategyKind::VirtualGoverned => {
+                GovernedConfigData::abi_decode_config(config_data)?.migratorParams
+            }
+        };
+        Ok(migrator_params)
+    }
+
+    /// Returns the hash of the creation code and constructor arguments the factory deploys the strategy with
+    pub fn init_code_hash(self, creation_code: &[u8], params: &LaunchParams) -> Result<B256, StrategyError> {
+        Ok(init_code_hash(creation_code, &self.constructor_args(params)?))
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.contract_name())
+    }
+}
+
+/// The arguments of StrategyFactory.initializeDistribution along with the immutables of the factory
+#[derive(Clone, Debug)]
+pub struct LaunchParams {
+    pub token: Address,
+    pub total_supply: U256,
+    pub config_data: Bytes,
+    pub position_manager: Address,
+    pub pool_manager: Address,
+}
+
+/// Errors returned when building the constructor arguments of a strategy
+#[derive(Debug)]
+pub enum StrategyError {
+    /// Mirrors IDistributionStrategy.InvalidAmount
+    InvalidAmount(U256, u128),
+    /// The config data does not decode to the layout expected by the factory
+    InvalidConfigData(alloy_sol_types::Error),
+}
+
+impl fmt::Display for StrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrategyError::InvalidAmount(amount, max_amount) => {
+                write!(f, "total supply {} is greater than {}", amount, max_amount)
+            }
+            StrategyError::InvalidConfigData(err) => write!(f, "invalid config data: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for StrategyError {}
+
+impl From<alloy_sol_types::Error> for StrategyError {
+    fn from(err: alloy_sol_types::Error) -> Self {
+        StrategyError::InvalidConfigData(err)
+    }
+}
+
+/// Equivalent to Solidity keccak256(abi.encodePacked(creationCode, constructorArgs))
+pub fn init_code_hash(creation_code: &[u8], constructor_args: &[u8]) -> B256 {
+    keccak256([creation_code, constructor_args].concat())
+}
+
+// `Default::default` is not const
+const HOOK_PERMISSIONS_NONE: HookPermissions = HookPermissions::from_flags(0);
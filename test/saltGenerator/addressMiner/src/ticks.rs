@@ -0,0 +1,388 @@
+use crate::math::mul_div;
+use crate::pricing::{MAX_SQRT_PRICE, MIN_SQRT_PRICE, Q96};
+use alloy_primitives::{uint, I256, U160, U256};
+use std::fmt;
+
+/// `TickMath.MIN_TICK`
+pub const MIN_TICK: i32 = -887272;
+/// `TickMath.MAX_TICK`
+pub const MAX_TICK: i32 = -MIN_TICK;
+/// `TickMath.MIN_TICK_SPACING`
+pub const MIN_TICK_SPACING: i32 = 1;
+/// `TickMath.MAX_TICK_SPACING`
+pub const MAX_TICK_SPACING: i32 = i16::MAX as i32;
+
+/// The reverts of `TickMath` and `LiquidityAmounts`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TickError {
+    InvalidTick { tick: i32 },
+    InvalidSqrtPrice { sqrt_price_x96: U160 },
+    /// `LiquidityAmounts` reverts when the liquidity does not fit in 128 bits
+    LiquidityOverflow { liquidity: U256 },
+    /// `FullMath.mulDiv` reverts without a reason when the result overflows
+    MulDivOverflow,
+}
+
+impl fmt::Display for TickError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TickError::InvalidTick { tick } => write!(f, "InvalidTick({})", tick),
+            TickError::InvalidSqrtPrice { sqrt_price_x96 } => write!(f, "InvalidSqrtPrice({})", sqrt_price_x96),
+            TickError::LiquidityOverflow { liquidity } => write!(f, "liquidity overflow ({})", liquidity),
+            TickError::MulDivOverflow => write!(f, "FullMath.mulDiv overflow"),
+        }
+    }
+}
+
+impl std::error::Error for TickError {}
+
+/// `TickMath.minUsableTick`: the lowest tick that is a multiple of the tick spacing, which must be positive
+pub fn min_usable_tick(tick_spacing: i32) -> i32 {
+    MIN_TICK / tick_spacing * tick_spacing
+}
+
+/// `TickMath.maxUsableTick`: the highest tick that is a multiple of the tick spacing, which must be positive
+pub fn max_usable_tick(tick_spacing: i32) -> i32 {
+    MAX_TICK / tick_spacing * tick_spacing
+}
+
+/// `TickMath.getSqrtPriceAtTick`: `sqrt(1.0001^tick) * 2^96` rounded up
+pub fn get_sqrt_price_at_tick(tick: i32) -> Result<U160, TickError> {
+    // Q128 multipliers of `1 / sqrt(1.0001)^(2^i)` for each bit i of the absolute tick
+    const RATIOS: [U256; 19] = uint!([
+        0xfff97272373d413259a46990580e213a_U256,
+        0xfff2e50f5f656932ef12357cf3c7fdcc_U256,
+        0xffe5caca7e10e4e61c3624eaa0941cd0_U256,
+        0xffcb9843d60f6159c9db58835c926644_U256,
+        0xff973b41fa98c081472e6896dfb254c0_U256,
+        0xff2ea16466c96a3843ec78b326b52861_U256,
+        0xfe5dee046a99a2a811c461f1969c3053_U256,
+        0xfcbe86c7900a88aedcffc83b479aa3a4_U256,
+        0xf987a7253ac413176f2b074cf7815e54_U256,
+        0xf3392b0822b70005940c7a398e4b70f3_U256,
+        0xe7159475a2c29b7443b29c7fa6e889d9_U256,
+        0xd097f3bdfd2022b8845ad8f792aa5825_U256,
+        0xa9f746462d870fdf8a65dc1f90e061e5_U256,
+        0x70d869a156d2a1b890bb3df62baf32f7_U256,
+        0x31be135f97d08fd981231505542fcfa6_U256,
+        0x9aa508b5b7a84e1c677de54f3e99bc9_U256,
+        0x5d6af8dedb81196699c329225ee604_U256,
+        0x2216e584f5fa1ea926041bedfe98_U256,
+        0x48a170391f7dc42444e8fa2_U256,
+    ]);
+
+    let abs_tick = tick.unsigned_abs();
+    if abs_tick > MAX_TICK as u32 {
+        return Err(TickError::InvalidTick { tick });
+    }
+
+    let mut price = if abs_tick & 1 != 0 {
+        uint!(0xfffcb933bd6fad37aa2d162d1a594001_U256)
+    } else {
+        U256::from(1) << 128
+    };
+    for (bit, ratio) in RATIOS.iter().enumerate() {
+        if abs_tick & (2 << bit) != 0 {
+            price = (price * ratio) >> 128;
+        }
+    }
+    if tick > 0 {
+        price = U256::MAX / price;
+    }
+
+    // Q128.128 to Q64.96, rounding up so that `getTickAtSqrtPrice` of the result is the tick
+    Ok(U160::from((price + U256::from(u32::MAX)) >> 32))
+}
+
+/// `TickMath.getTickAtSqrtPrice`: the greatest tick whose sqrt price is at most the sqrt price, which must be in
+/// `[MIN_SQRT_PRICE, MAX_SQRT_PRICE)`
+pub fn get_tick_at_sqrt_price(sqrt_price_x96: U160) -> Result<i32, TickError> {
+    if sqrt_price_x96 < MIN_SQRT_PRICE || sqrt_price_x96 >= MAX_SQRT_PRICE {
+        return Err(TickError::InvalidSqrtPrice { sqrt_price_x96 });
+    }
+    let price: U256 = U256::from(sqrt_price_x96) << 32;
+
+    // Normalizes the price to a Q1.127 mantissa in [1, 2) and takes the integer part of log2 from its exponent
+    let msb = price.bit_len() - 1;
+    let mut r = if msb >= 128 { price >> (msb - 127) } else { price << (127 - msb) };
+    let mut log_2: I256 = I256::unchecked_from(msb as i64 - 128) << 64;
+
+    // 14 fractional bits of log2 by repeated squaring of the mantissa
+    for bit in (50..64).rev() {
+        r = (r * r) >> 127;
+        let f: U256 = r >> 128;
+        log_2 |= I256::from_raw(f << bit);
+        r >>= f.to::<usize>();
+    }
+
+    // log_sqrt(1.0001)(price) as Q128.128, and the bounds of its error
+    let log_sqrt10001: I256 = log_2 * I256::from_raw(uint!(255738958999603826347141_U256));
+    let tick_low =
+        (log_sqrt10001 - I256::from_raw(uint!(3402992956809132418596140100660247210_U256))).asr(128).low_i32();
+    let tick_high =
+        (log_sqrt10001 + I256::from_raw(uint!(291339464771989622907027621153398088495_U256))).asr(128).low_i32();
+
+    if tick_low == tick_high || get_sqrt_price_at_tick(tick_high)? > sqrt_price_x96 {
+        Ok(tick_low)
+    } else {
+        Ok(tick_high)
+    }
+}
+
+/// `TickCalculations.tickSpacingToMaxLiquidityPerTick`: the max liquidity per tick of `Pool`, if every usable tick
+/// held the same liquidity. The tick spacing cannot be 0.
+pub fn tick_spacing_to_max_liquidity_per_tick(tick_spacing: i32) -> u128 {
+    let min_tick = MIN_TICK / tick_spacing - (MIN_TICK % tick_spacing < 0) as i32;
+    let max_tick = MAX_TICK / tick_spacing;
+    let num_ticks = max_tick - min_tick + 1;
+    // A negative tick spacing makes the count negative, a huge unsigned number in the EVM
+    if num_ticks <= 0 {
+        return 0;
+    }
+    u128::MAX / num_ticks as u128
+}
+
+/// `TickCalculations.tickFloor`: rounds the tick down to a multiple of the tick spacing, which cannot be 0.
+/// The result wraps around like the unchecked `int24` arithmetic.
+pub fn tick_floor(tick: i32, tick_spacing: i32) -> i32 {
+    let remainder = tick % tick_spacing;
+    int24(if remainder >= 0 { tick - remainder } else { tick - remainder - tick_spacing })
+}
+
+/// `TickCalculations.tickStrictCeil`: rounds the tick up to the next multiple of the tick spacing, even if it is
+/// one already. The tick spacing cannot be 0 and the result wraps around like the unchecked `int24` arithmetic.
+pub fn tick_strict_ceil(tick: i32, tick_spacing: i32) -> i32 {
+    let remainder = tick % tick_spacing;
+    int24(if remainder >= 0 { tick + tick_spacing - remainder } else { tick - remainder })
+}
+
+/// `LiquidityAmounts.getLiquidityForAmount0`: the liquidity of an amount of currency0 between two sqrt prices
+pub fn get_liquidity_for_amount0(
+    sqrt_price_a_x96: U160,
+    sqrt_price_b_x96: U160,
+    amount0: u128,
+) -> Result<u128, TickError> {
+    let (lower, upper) = sorted(sqrt_price_a_x96, sqrt_price_b_x96);
+    let (lower, upper) = (U256::from(lower), U256::from(upper));
+    // Both sqrt prices fit in 160 bits, so the product divided by Q96 fits in 256 bits
+    let intermediate = mul_div(lower, upper, Q96).ok_or(TickError::MulDivOverflow)?;
+    let liquidity = mul_div(U256::from(amount0), intermediate, upper - lower).ok_or(TickError::MulDivOverflow)?;
+    to_u128(liquidity)
+}
+
+/// `LiquidityAmounts.getLiquidityForAmount1`: the liquidity of an amount of currency1 between two sqrt prices
+pub fn get_liquidity_for_amount1(
+    sqrt_price_a_x96: U160,
+    sqrt_price_b_x96: U160,
+    amount1: u128,
+) -> Result<u128, TickError> {
+    let (lower, upper) = sorted(sqrt_price_a_x96, sqrt_price_b_x96);
+    let (lower, upper) = (U256::from(lower), U256::from(upper));
+    let liquidity = mul_div(U256::from(amount1), Q96, upper - lower).ok_or(TickError::MulDivOverflow)?;
+    to_u128(liquidity)
+}
+
+/// `LiquidityAmounts.getLiquidityForAmounts`: the most liquidity the amounts can provide between two sqrt prices
+/// at the current sqrt price
+pub fn get_liquidity_for_amounts(
+    sqrt_price_x96: U160,
+    sqrt_price_a_x96: U160,
+    sqrt_price_b_x96: U160,
+    amount0: u128,
+    amount1: u128,
+) -> Result<u128, TickError> {
+    let (lower, upper) = sorted(sqrt_price_a_x96, sqrt_price_b_x96);
+    if sqrt_price_x96 <= lower {
+        get_liquidity_for_amount0(lower, upper, amount0)
+    } else if sqrt_price_x96 < upper {
+        let liquidity0 = get_liquidity_for_amount0(sqrt_price_x96, upper, amount0)?;
+        let liquidity1 = get_liquidity_for_amount1(lower, sqrt_price_x96, amount1)?;
+        Ok(liquidity0.min(liquidity1))
+    } else {
+        get_liquidity_for_amount1(lower, upper, amount1)
+    }
+}
+
+/// Truncates to `int24` like a Solidity cast, keeping the low 24 bits with their sign
+fn int24(value: i32) -> i32 {
+    (value << 8) >> 8
+}
+
+/// Returns the sqrt prices in ascending order
+fn sorted(sqrt_price_a_x96: U160, sqrt_price_b_x96: U160) -> (U160, U160) {
+    if sqrt_price_a_x96 > sqrt_price_b_x96 {
+        (sqrt_price_b_x96, sqrt_price_a_x96)
+    } else {
+        (sqrt_price_a_x96, sqrt_price_b_x96)
+    }
+}
+
+fn to_u128(liquidity: U256) -> Result<u128, TickError> {
+    if liquidity > U256::from(u128::MAX) {
+        return Err(TickError::LiquidityOverflow { liquidity });
+    }
+    Ok(liquidity.to::<u128>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// `encodePriceSqrt` of the v3 and v4 tests, `sqrt(reserve1 / reserve0) * 2^96` rounded down
+    fn encode_price_sqrt(reserve1: u64, reserve0: u64) -> U160 {
+        let price_x192: U256 = (U256::from(reserve1) << 192) / U256::from(reserve0);
+        U160::from(price_x192.root(2))
+    }
+
+    #[test]
+    fn sqrt_price_at_tick_vectors() {
+        assert_eq!(get_sqrt_price_at_tick(MIN_TICK), Ok(MIN_SQRT_PRICE));
+        assert_eq!(get_sqrt_price_at_tick(MIN_TICK + 1), Ok(uint!(4295343490_U160)));
+        assert_eq!(get_sqrt_price_at_tick(0), Ok(U160::from(Q96)));
+        assert_eq!(
+            get_sqrt_price_at_tick(MAX_TICK - 1),
+            Ok(uint!(1461373636630004318706518188784493106690254656249_U160))
+        );
+        assert_eq!(get_sqrt_price_at_tick(MAX_TICK), Ok(MAX_SQRT_PRICE));
+        assert_eq!(get_sqrt_price_at_tick(MIN_TICK - 1), Err(TickError::InvalidTick { tick: MIN_TICK - 1 }));
+        assert_eq!(get_sqrt_price_at_tick(MAX_TICK + 1), Err(TickError::InvalidTick { tick: MAX_TICK + 1 }));
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_vectors() {
+        assert_eq!(get_tick_at_sqrt_price(MIN_SQRT_PRICE), Ok(MIN_TICK));
+        assert_eq!(get_tick_at_sqrt_price(uint!(4295343490_U160)), Ok(MIN_TICK + 1));
+        assert_eq!(get_tick_at_sqrt_price(U160::from(Q96)), Ok(0));
+        assert_eq!(get_tick_at_sqrt_price(MAX_SQRT_PRICE - U160::from(1)), Ok(MAX_TICK - 1));
+        let below = MIN_SQRT_PRICE - U160::from(1);
+        assert_eq!(get_tick_at_sqrt_price(below), Err(TickError::InvalidSqrtPrice { sqrt_price_x96: below }));
+        assert_eq!(
+            get_tick_at_sqrt_price(MAX_SQRT_PRICE),
+            Err(TickError::InvalidSqrtPrice { sqrt_price_x96: MAX_SQRT_PRICE })
+        );
+    }
+
+    #[test]
+    fn usable_ticks() {
+        assert_eq!((min_usable_tick(1), max_usable_tick(1)), (MIN_TICK, MAX_TICK));
+        assert_eq!((min_usable_tick(60), max_usable_tick(60)), (-887220, 887220));
+        assert_eq!((min_usable_tick(200), max_usable_tick(200)), (-887200, 887200));
+    }
+
+    // Vectors of test/libraries/TickCalculations.t.sol
+    #[test]
+    fn max_liquidity_per_tick_vectors() {
+        assert_eq!(tick_spacing_to_max_liquidity_per_tick(10), 1917559095893846719543856547154045);
+        assert_eq!(tick_spacing_to_max_liquidity_per_tick(60), 11505354575363080317263139282924270);
+        assert_eq!(tick_spacing_to_max_liquidity_per_tick(200), 38345995821606768476828330790147420);
+        assert_eq!(tick_spacing_to_max_liquidity_per_tick(MIN_TICK_SPACING), 191757530477355301479181766273477);
+        assert_eq!(tick_spacing_to_max_liquidity_per_tick(MAX_TICK_SPACING), 6076470837873901133274546561281575204);
+        assert_eq!(tick_spacing_to_max_liquidity_per_tick(2302), 440780268032303709149448973357212709);
+    }
+
+    #[test]
+    fn tick_floor_vectors() {
+        assert_eq!(tick_floor(1, 1), 1);
+        assert_eq!(tick_floor(-1, 1), -1);
+        assert_eq!(tick_floor(0, 1), 0);
+        assert_eq!(tick_floor(-1, 2), -2);
+        assert_eq!(tick_floor(1, 2), 0);
+        assert_eq!(tick_floor(-1, 3), -3);
+        assert_eq!(tick_floor(1, 3), 0);
+    }
+
+    #[test]
+    fn tick_strict_ceil_vectors() {
+        assert_eq!(tick_strict_ceil(1, 1), 2);
+        assert_eq!(tick_strict_ceil(-1, 1), 0);
+        assert_eq!(tick_strict_ceil(0, 1), 1);
+        assert_eq!(tick_strict_ceil(-1, 2), 0);
+        assert_eq!(tick_strict_ceil(1, 2), 2);
+        assert_eq!(tick_strict_ceil(-1, 3), 0);
+        assert_eq!(tick_strict_ceil(1, 3), 3);
+        assert_eq!(tick_strict_ceil(-1, 4), 0);
+        assert_eq!(tick_strict_ceil(1, 4), 4);
+    }
+
+    // Vectors of the v3-periphery LiquidityAmounts tests
+    #[test]
+    fn liquidity_for_amounts_vectors() {
+        let (lower, upper) = (encode_price_sqrt(100, 110), encode_price_sqrt(110, 100));
+        assert_eq!(get_liquidity_for_amounts(encode_price_sqrt(1, 1), lower, upper, 100, 200), Ok(2148));
+        assert_eq!(get_liquidity_for_amounts(encode_price_sqrt(99, 110), lower, upper, 100, 200), Ok(1048));
+        assert_eq!(get_liquidity_for_amounts(encode_price_sqrt(111, 100), lower, upper, 100, 200), Ok(2097));
+        assert_eq!(get_liquidity_for_amounts(lower, lower, upper, 100, 200), Ok(1048));
+        assert_eq!(get_liquidity_for_amounts(upper, lower, upper, 100, 200), Ok(2097));
+    }
+
+    fn sqrt_price() -> impl Strategy<Value = U160> {
+        any::<[u8; 20]>().prop_map(|bytes| {
+            let range = MAX_SQRT_PRICE - MIN_SQRT_PRICE;
+            MIN_SQRT_PRICE + U160::from_be_bytes(bytes) % range
+        })
+    }
+
+    fn tick_spacing() -> impl Strategy<Value = i32> {
+        MIN_TICK_SPACING..=MAX_TICK_SPACING
+    }
+
+    proptest! {
+        #[test]
+        fn sqrt_price_increases_with_tick(tick in MIN_TICK..MAX_TICK) {
+            prop_assert!(get_sqrt_price_at_tick(tick)? < get_sqrt_price_at_tick(tick + 1)?);
+        }
+
+        #[test]
+        fn tick_at_sqrt_price_of_tick(tick in MIN_TICK..MAX_TICK) {
+            prop_assert_eq!(get_tick_at_sqrt_price(get_sqrt_price_at_tick(tick)?)?, tick);
+        }
+
+        #[test]
+        fn tick_at_sqrt_price_is_greatest_below(sqrt_price_x96 in sqrt_price()) {
+            let tick = get_tick_at_sqrt_price(sqrt_price_x96)?;
+            prop_assert!(get_sqrt_price_at_tick(tick)? <= sqrt_price_x96);
+            prop_assert!(get_sqrt_price_at_tick(tick + 1)? > sqrt_price_x96);
+        }
+
+        #[test]
+        fn tick_floor_is_multiple_below(tick in MIN_TICK..=MAX_TICK, tick_spacing in tick_spacing()) {
+            let floor = tick_floor(tick, tick_spacing);
+            prop_assert_eq!(floor % tick_spacing, 0);
+            prop_assert!(floor <= tick && tick - floor < tick_spacing);
+        }
+
+        #[test]
+        fn tick_strict_ceil_is_multiple_above(tick in MIN_TICK..=MAX_TICK, tick_spacing in tick_spacing()) {
+            let ceil = tick_strict_ceil(tick, tick_spacing);
+            prop_assert_eq!(ceil % tick_spacing, 0);
+            prop_assert!(ceil > tick && ceil - tick <= tick_spacing);
+        }
+
+        #[test]
+        fn usable_ticks_are_within_bounds(tick_spacing in tick_spacing()) {
+            let (min_tick, max_tick) = (min_usable_tick(tick_spacing), max_usable_tick(tick_spacing));
+            prop_assert!(min_tick >= MIN_TICK && min_tick - tick_spacing < MIN_TICK);
+            prop_assert!(max_tick <= MAX_TICK && max_tick + tick_spacing > MAX_TICK);
+            prop_assert_eq!(min_tick, -max_tick);
+        }
+
+        #[test]
+        fn liquidity_in_range_is_the_lesser_side(
+            sqrt_prices in [sqrt_price(), sqrt_price(), sqrt_price()],
+            amount0 in any::<u64>(),
+            amount1 in any::<u64>(),
+        ) {
+            let mut sqrt_prices = sqrt_prices;
+            sqrt_prices.sort();
+            let [lower, sqrt_price_x96, upper] = sqrt_prices;
+            prop_assume!(lower < sqrt_price_x96 && sqrt_price_x96 < upper);
+            let (amount0, amount1) = (amount0 as u128, amount1 as u128);
+            // Overflows on either side revert, as `getLiquidityForAmounts` computes both
+            let expected = get_liquidity_for_amount0(sqrt_price_x96, upper, amount0).and_then(|liquidity0| {
+                Ok(liquidity0.min(get_liquidity_for_amount1(lower, sqrt_price_x96, amount1)?))
+            });
+            prop_assert_eq!(get_liquidity_for_amounts(sqrt_price_x96, upper, lower, amount0, amount1), expected);
+        }
+    }
+}
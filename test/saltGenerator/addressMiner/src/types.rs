@@ -0,0 +1,209 @@
+use alloy_sol_types::{sol, SolValue};
+
+sol! {
+    /// Mirrors src/types/MigratorParameters.sol
+    #[derive(Debug, PartialEq, Eq)]
+    struct MigratorParameters {
+        uint64 migrationBlock;
+        address currency;
+        uint24 poolLPFee;
+        int24 poolTickSpacing;
+        uint24 tokenSplit;
+        address initializerFactory;
+        address positionRecipient;
+        uint64 sweepBlock;
+        address operator;
+        uint128 maxCurrencyAmountForLP;
+    }
+
+    /// Mirrors LBPInitializationParams of src/interfaces/ILBPInitializer.sol
+    #[derive(Debug, PartialEq, Eq)]
+    struct LBPInitializationParams {
+        uint256 initialPriceX96;
+        uint256 tokensSold;
+        uint256 currencyRaised;
+    }
+
+    /// Mirrors src/types/MigrationData.sol
+    #[derive(Debug, PartialEq, Eq)]
+    struct MigrationData {
+        uint160 sqrtPriceX96;
+        uint128 fullRangeTokenAmount;
+        uint128 fullRangeCurrencyAmount;
+        uint128 leftoverCurrency;
+        uint128 liquidity;
+    }
+
+    /// Mirrors PoolKey of v4-core, the currencies and hooks as addresses
+    #[derive(Debug, PartialEq, Eq)]
+    struct PoolKey {
+        address currency0;
+        address currency1;
+        uint24 fee;
+        int24 tickSpacing;
+        address hooks;
+    }
+
+    /// Mirrors src/types/Distribution.sol
+    #[derive(Debug, PartialEq, Eq)]
+    struct Distribution {
+        address strategy;
+        uint128 amount;
+        bytes configData;
+    }
+
+    /// The configData decoded by FullRangeLBPStrategyFactory
+    #[derive(Debug, PartialEq, Eq)]
+    struct FullRangeConfigData {
+        MigratorParameters migratorParams;
+        bytes auctionParams;
+    }
+
+    /// The configData decoded by AdvancedLBPStrategyFactory
+    #[derive(Debug, PartialEq, Eq)]
+    struct AdvancedConfigData {
+        MigratorParameters migratorParams;
+        bytes auctionParams;
+        bool createOneSidedTokenPosition;
+        bool createOneSidedCurrencyPosition;
+    }
+
+    /// The configData decoded by GovernedLBPStrategyFactory
+    #[derive(Debug, PartialEq, Eq)]
+    struct GovernedConfigData {
+        address governanceAddress;
+        MigratorParameters migratorParams;
+        bytes auctionParams;
+    }
+
+    /// The configData decoded by MerkleClaimFactory
+    #[derive(Debug, PartialEq, Eq)]
+    struct MerkleClaimConfigData {
+        bytes32 merkleRoot;
+        address owner;
+        uint256 endTime;
+    }
+}
+
+/// The configData passed to a strategy factory
+/// Factories decode it as a list of values, so it is encoded as `abi.encode(field0, field1, ...)`
+/// rather than as `abi.encode(struct)` which would prefix dynamic structs with an offset
+pub trait ConfigData: Sized {
+    fn abi_encode_config(&self) -> Vec<u8>;
+
+    fn abi_decode_config(data: &[u8]) -> alloy_sol_types::Result<Self>;
+}
+
+macro_rules! impl_config_data {
+    ($($config:ty),*) => {
+        $(
+            impl ConfigData for $config {
+                fn abi_encode_config(&self) -> Vec<u8> {
+                    self.abi_encode_params()
+                }
+
+                fn abi_decode_config(data: &[u8]) -> alloy_sol_types::Result<Self> {
+                    Self::abi_decode_params(data, true)
+                }
+            }
+        )*
+    };
+}
+
+impl_config_data!(FullRangeConfigData, AdvancedConfigData, GovernedConfigData, MerkleClaimConfigData);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::aliases::{I24, U24};
+    use alloy_primitives::{address, b256, hex, Address, Bytes, U256};
+
+    fn migrator_params() -> MigratorParameters {
+        MigratorParameters {
+            migrationBlock: 100,
+            currency: Address::ZERO,
+            poolLPFee: U24::from(3000),
+            poolTickSpacing: I24::unchecked_from(-60),
+            tokenSplit: U24::from(5_000_000),
+            initializerFactory: address!("3333333333333333333333333333333333333333"),
+            positionRecipient: address!("2222222222222222222222222222222222222222"),
+            sweepBlock: 200,
+            operator: address!("4444444444444444444444444444444444444444"),
+            maxCurrencyAmountForLP: u128::MAX,
+        }
+    }
+
+    fn round_trip<T: ConfigData + PartialEq + std::fmt::Debug>(config: T) {
+        assert_eq!(T::abi_decode_config(&config.abi_encode_config()).unwrap(), config);
+    }
+
+    #[test]
+    fn config_data_round_trips() {
+        let auction_params = Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]);
+        round_trip(FullRangeConfigData { migratorParams: migrator_params(), auctionParams: auction_params.clone() });
+        round_trip(AdvancedConfigData {
+            migratorParams: migrator_params(),
+            auctionParams: auction_params.clone(),
+            createOneSidedTokenPosition: true,
+            createOneSidedCurrencyPosition: false,
+        });
+        round_trip(GovernedConfigData {
+            governanceAddress: address!("5555555555555555555555555555555555555555"),
+            migratorParams: migrator_params(),
+            auctionParams: Bytes::new(),
+        });
+        round_trip(MerkleClaimConfigData {
+            merkleRoot: b256!("0101010101010101010101010101010101010101010101010101010101010101"),
+            owner: address!("6666666666666666666666666666666666666666"),
+            endTime: U256::from(86_401),
+        });
+    }
+
+    #[test]
+    fn advanced_config_data_matches_abi_encode() {
+        // abi.encode(migratorParams, hex"deadbeef", true, false) as decoded by AdvancedLBPStrategyFactory
+        let encoded = hex::decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000064",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000bb8",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffc4",
+            "00000000000000000000000000000000000000000000000000000000004c4b40",
+            "0000000000000000000000003333333333333333333333333333333333333333",
+            "0000000000000000000000002222222222222222222222222222222222222222",
+            "00000000000000000000000000000000000000000000000000000000000000c8",
+            "0000000000000000000000004444444444444444444444444444444444444444",
+            "00000000000000000000000000000000ffffffffffffffffffffffffffffffff",
+            "00000000000000000000000000000000000000000000000000000000000001a0",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            "deadbeef00000000000000000000000000000000000000000000000000000000",
+        ))
+        .unwrap();
+        let config = AdvancedConfigData {
+            migratorParams: migrator_params(),
+            auctionParams: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            createOneSidedTokenPosition: true,
+            createOneSidedCurrencyPosition: false,
+        };
+        assert_eq!(config.abi_encode_config(), encoded);
+        assert_eq!(AdvancedConfigData::abi_decode_config(&encoded).unwrap(), config);
+    }
+
+    #[test]
+    fn merkle_claim_config_data_matches_abi_encode() {
+        // abi.encode(merkleRoot, owner, endTime) of MerkleClaimFactory.t.sol, static so without any offset
+        let config = MerkleClaimConfigData {
+            merkleRoot: b256!("0101010101010101010101010101010101010101010101010101010101010101"),
+            owner: address!("6666666666666666666666666666666666666666"),
+            endTime: U256::from(86_401),
+        };
+        let encoded = hex::decode(concat!(
+            "0101010101010101010101010101010101010101010101010101010101010101",
+            "0000000000000000000000006666666666666666666666666666666666666666",
+            "0000000000000000000000000000000000000000000000000000000000015181",
+        ))
+        .unwrap();
+        assert_eq!(config.abi_encode_config(), encoded);
+    }
+}
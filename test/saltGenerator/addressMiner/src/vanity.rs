@@ -0,0 +1,330 @@
+use alloy_primitives::Address;
+use regex::Regex;
+use std::fmt;
+
+/// Number of hex characters in an address
+const ADDRESS_NIBBLES: usize = 40;
+
+#[derive(Clone, Debug)]
+pub enum VanityError {
+    /// The pattern has a character that is not hex, or not `?` in a mask
+    InvalidHex(String),
+    /// The pattern does not fit in an address
+    TooLong(String),
+    /// Two patterns require different characters at the same position
+    Conflict { position: usize, existing: char, new: char },
+    /// A pattern requires a character the hook permission bits rule out
+    ConflictsWithHookFlags { position: usize, character: char },
+    InvalidRegex(regex::Error),
+}
+
+impl fmt::Display for VanityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VanityError::InvalidHex(pattern) => write!(f, "Invalid hex pattern {:?}", pattern),
+            VanityError::TooLong(pattern) => write!(f, "Pattern {:?} does not fit in an address", pattern),
+            VanityError::Conflict { position, existing, new } => {
+                write!(f, "Patterns require both {:?} and {:?} at position {}", existing, new, position)
+            }
+            VanityError::ConflictsWithHookFlags { position, character } => {
+                write!(f, "Pattern requires {:?} at position {}, which the hook permissions rule out", character, position)
+            }
+            VanityError::InvalidRegex(err) => write!(f, "Invalid regex: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for VanityError {}
+
+/// The vanity requirements of an address, all of which must hold.
+/// Prefixes, suffixes and masks such as `dead????beef` fix characters at given positions, `contains` requires a
+/// substring anywhere and the regex is matched against the EIP-55 checksummed address without `0x`.
+/// Fixed characters and substrings compare case-insensitively unless case sensitive, then against the checksum case.
+#[derive(Clone, Debug)]
+pub struct Vanity {
+    // The required character at each position of the address, from the left
+    nibbles: [Option<char>; ADDRESS_NIBBLES],
+    contains: Vec<String>,
+    regex: Option<Regex>,
+    case_sensitive: bool,
+}
+
+impl Default for Vanity {
+    fn default() -> Self {
+        Self { nibbles: [None; ADDRESS_NIBBLES], contains: Vec::new(), regex: None, case_sensitive: false }
+    }
+}
+
+impl Vanity {
+    /// Requires the address to start with the hex prefix
+    pub fn with_prefix(self, prefix: &str) -> Result<Self, VanityError> {
+        self.with_nibbles(prefix, 0, false)
+    }
+
+    /// Requires the address to end with the hex suffix
+    pub fn with_suffix(self, suffix: &str) -> Result<Self, VanityError> {
+        let offset = ADDRESS_NIBBLES.checked_sub(suffix.len()).ok_or(VanityError::TooLong(suffix.to_string()))?;
+        self.with_nibbles(suffix, offset, false)
+    }
+
+    /// Requires the address to match a mask of hex characters and `?` wildcards from the left, e.g. `0xdead????beef`
+    pub fn with_mask(self, mask: &str) -> Result<Self, VanityError> {
+        self.with_nibbles(mask.strip_prefix("0x").unwrap_or(mask), 0, true)
+    }
+
+    /// Requires the address to contain the hex string
+    pub fn with_contains(mut self, text: &str) -> Result<Self, VanityError> {
+        if !text.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(VanityError::InvalidHex(text.to_string()));
+        }
+        if text.len() > ADDRESS_NIBBLES {
+            return Err(VanityError::TooLong(text.to_string()));
+        }
+        self.contains.push(text.to_string());
+        Ok(self)
+    }
+
+    /// Requires the EIP-55 checksummed address without `0x` to match the regex
+    pub fn with_regex(mut self, regex: &str) -> Result<Self, VanityError> {
+        self.regex = Some(Regex::new(regex).map_err(VanityError::InvalidRegex)?);
+        Ok(self)
+    }
+
+    /// Compares fixed characters and substrings against the checksum case of the address
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    fn with_nibbles(mut self, pattern: &str, offset: usize, wildcards: bool) -> Result<Self, VanityError> {
+        if offset + pattern.len() > ADDRESS_NIBBLES {
+            return Err(VanityError::TooLong(pattern.to_string()));
+        }
+        for (i, c) in pattern.chars().enumerate() {
+            if wildcards && c == '?' {
+                continue;
+            }
+            if !c.is_ascii_hexdigit() {
+                return Err(VanityError::InvalidHex(pattern.to_string()));
+            }
+            let position = offset + i;
+            match self.nibbles[position] {
+                Some(existing) if !existing.eq_ignore_ascii_case(&c) => {
+                    return Err(VanityError::Conflict { position, existing, new: c });
+                }
+                _ => self.nibbles[position] = Some(c),
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns true if there is no requirement
+    pub fn is_empty(&self) -> bool {
+        self.nibbles.iter().all(Option::is_none) && self.contains.is_empty() && self.regex.is_none()
+    }
+
+    /// Returns true if the address fulfills all the requirements
+    pub fn matches(&self, address: Address) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let checksummed = address.to_checksum(None);
+        let checksummed = &checksummed[2..];
+        let lowercase = checksummed.to_ascii_lowercase();
+
+        let nibbles_match = self.nibbles.iter().zip(checksummed.chars().zip(lowercase.chars())).all(
+            |(nibble, (checksum_char, lowercase_char))| match nibble {
+                None => true,
+                Some(c) if self.case_sensitive => *c == checksum_char,
+                Some(c) => c.to_ascii_lowercase() == lowercase_char,
+            },
+        );
+        let contains_match = self.contains.iter().all(|text| {
+            if self.case_sensitive {
+                checksummed.contains(text.as_str())
+            } else {
+                lowercase.contains(&text.to_ascii_lowercase())
+            }
+        });
+        let regex_match = self.regex.as_ref().is_none_or(|regex| regex.is_match(checksummed));
+        nibbles_match && contains_match && regex_match
+    }
+
+    /// Checks that the fixed characters can coexist with the hook permission bits in the low 14 bits of the address
+    pub fn check_hook_flags(&self, flags: u16) -> Result<(), VanityError> {
+        for (position, nibble) in self.nibbles.iter().enumerate() {
+            let (Some(c), Some((value, mask))) = (nibble, hook_nibble(flags, position)) else {
+                continue;
+            };
+            if c.to_digit(16).unwrap() as u16 & mask != value {
+                return Err(VanityError::ConflictsWithHookFlags { position, character: *c });
+            }
+        }
+        Ok(())
+    }
+
+    /// Estimates the probability that an address with the right hook permission bits fulfills the requirements.
+    /// Returns `None` with a regex, the probability of which cannot be estimated.
+    pub fn probability(&self, flags: u16) -> Option<f64> {
+        if self.regex.is_some() {
+            return None;
+        }
+        let mut probability = 1.0;
+        for (position, nibble) in self.nibbles.iter().enumerate() {
+            let Some(c) = nibble else {
+                continue;
+            };
+            // Bits fixed by the hook permissions are already right, a letter has the required case half of the time
+            let free_bits = match hook_nibble(flags, position) {
+                Some((_, mask)) => 4 - mask.count_ones(),
+                None => 4,
+            };
+            probability /= (1u32 << free_bits) as f64;
+            if self.case_sensitive && c.is_ascii_alphabetic() {
+                probability /= 2.0;
+            }
+        }
+        for text in &self.contains {
+            // Union bound over the positions the text could start at
+            let mut text_probability = (1.0 / 16.0f64).powi(text.len() as i32);
+            if self.case_sensitive {
+                text_probability /= 2.0f64.powi(text.chars().filter(char::is_ascii_alphabetic).count() as i32);
+            }
+            probability *= ((ADDRESS_NIBBLES - text.len() + 1) as f64 * text_probability).min(1.0);
+        }
+        Some(probability)
+    }
+}
+
+impl fmt::Display for Vanity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.nibbles.iter().any(Option::is_some) {
+            parts.push(format!("0x{}", self.nibbles.iter().map(|nibble| nibble.unwrap_or('?')).collect::<String>()));
+        }
+        parts.extend(self.contains.iter().map(|text| format!("contains {}", text)));
+        if let Some(regex) = &self.regex {
+            parts.push(format!("regex {}", regex));
+        }
+        if parts.is_empty() {
+            return write!(f, "none");
+        }
+        write!(f, "{}", parts.join(", "))?;
+        if self.case_sensitive {
+            write!(f, " (case sensitive)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the value and the mask of the bits of the nibble at the position fixed by the hook permission bits
+fn hook_nibble(flags: u16, position: usize) -> Option<(u16, u16)> {
+    // The low 14 bits are the last three nibbles and the two low bits of the one before them
+    let shift = (ADDRESS_NIBBLES - 1 - position) * 4;
+    if shift >= 14 {
+        return None;
+    }
+    let mask = 0xf & ((1u16 << (14 - shift)) - 1);
+    Some(((flags >> shift) & mask, mask))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::BEFORE_INITIALIZE_FLAG as BEFORE_INITIALIZE;
+    use alloy_primitives::address;
+
+    // The EIP-55 example, checksummed 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed
+    const ADDRESS: Address = address!("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed");
+
+    #[test]
+    fn hook_nibble_covers_the_low_14_bits() {
+        assert_eq!(hook_nibble(0x2abc, 39), Some((0xc, 0xf)));
+        assert_eq!(hook_nibble(0x2abc, 38), Some((0xb, 0xf)));
+        assert_eq!(hook_nibble(0x2abc, 37), Some((0xa, 0xf)));
+        // Only the two low bits of the fourth nibble from the right are hook flags
+        assert_eq!(hook_nibble(0x2abc, 36), Some((0x2, 0x3)));
+        assert_eq!(hook_nibble(0x3fff, 36), Some((0x3, 0x3)));
+        assert_eq!(hook_nibble(0x2abc, 35), None);
+        assert_eq!(hook_nibble(0x2abc, 0), None);
+    }
+
+    #[test]
+    fn fixed_characters_match_case_insensitively() {
+        let matches = |vanity: Result<Vanity, VanityError>| vanity.unwrap().matches(ADDRESS);
+        assert!(Vanity::default().matches(ADDRESS));
+        assert!(matches(Vanity::default().with_prefix("5AAEB6")));
+        assert!(!matches(Vanity::default().with_prefix("5aaeb7")));
+        assert!(matches(Vanity::default().with_suffix("BEAED")));
+        assert!(!matches(Vanity::default().with_suffix("beaee")));
+        assert!(matches(Vanity::default().with_mask("0x5a??b6")));
+        assert!(!matches(Vanity::default().with_mask("5a??b7")));
+        assert!(matches(Vanity::default().with_contains("9435e7")));
+        assert!(!matches(Vanity::default().with_contains("9435e8")));
+        assert!(matches(Vanity::default().with_prefix("5a").and_then(|vanity| vanity.with_suffix("ed"))));
+    }
+
+    #[test]
+    fn case_sensitive_characters_match_the_checksum() {
+        let matches = |vanity: Result<Vanity, VanityError>| vanity.unwrap().case_sensitive(true).matches(ADDRESS);
+        assert!(matches(Vanity::default().with_prefix("5aAe")));
+        assert!(!matches(Vanity::default().with_prefix("5aae")));
+        assert!(matches(Vanity::default().with_suffix("BeAed")));
+        assert!(matches(Vanity::default().with_contains("E7Ef")));
+        assert!(!matches(Vanity::default().with_contains("e7ef")));
+    }
+
+    #[test]
+    fn regex_matches_the_checksummed_address() {
+        assert!(Vanity::default().with_regex("^5aA").unwrap().matches(ADDRESS));
+        assert!(!Vanity::default().with_regex("^5aa").unwrap().matches(ADDRESS));
+        assert!(Vanity::default().with_regex("(?i)^5AA").unwrap().matches(ADDRESS));
+        assert!(matches!(Vanity::default().with_regex("("), Err(VanityError::InvalidRegex(_))));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        assert!(matches!(Vanity::default().with_prefix("xyz"), Err(VanityError::InvalidHex(_))));
+        assert!(matches!(Vanity::default().with_prefix("?"), Err(VanityError::InvalidHex(_))));
+        assert!(matches!(Vanity::default().with_contains("0x"), Err(VanityError::InvalidHex(_))));
+        assert!(matches!(Vanity::default().with_suffix(&"0".repeat(41)), Err(VanityError::TooLong(_))));
+        assert!(matches!(Vanity::default().with_mask(&"?".repeat(41)), Err(VanityError::TooLong(_))));
+        let conflict = Vanity::default().with_prefix("ab").unwrap().with_mask("?c");
+        assert!(matches!(conflict, Err(VanityError::Conflict { position: 1, existing: 'b', new: 'c' })));
+        // The same character in another case is not a conflict
+        assert!(Vanity::default().with_prefix("ab").unwrap().with_mask("?B").is_ok());
+    }
+
+    #[test]
+    fn check_hook_flags_rejects_characters_the_flags_rule_out() {
+        // The before initialize flag makes the fourth nibble from the right 2, 6, a or e, and the last three 0
+        let suffix = |suffix: &str| Vanity::default().with_suffix(suffix).unwrap().check_hook_flags(BEFORE_INITIALIZE);
+        assert!(suffix("2000").is_ok());
+        assert!(suffix("e000").is_ok());
+        assert!(matches!(
+            suffix("3000"),
+            Err(VanityError::ConflictsWithHookFlags { position: 36, character: '3' })
+        ));
+        assert!(matches!(
+            suffix("2001"),
+            Err(VanityError::ConflictsWithHookFlags { position: 39, character: '1' })
+        ));
+        assert!(Vanity::default().with_prefix("ffff").unwrap().check_hook_flags(BEFORE_INITIALIZE).is_ok());
+    }
+
+    #[test]
+    fn probability_counts_the_bits_left_free_by_the_flags() {
+        let probability = |vanity: Vanity| vanity.probability(BEFORE_INITIALIZE).unwrap();
+        assert_eq!(probability(Vanity::default()), 1.0);
+        assert_eq!(probability(Vanity::default().with_prefix("dead").unwrap()), 1.0 / 65536.0);
+        // The last three nibbles are fixed by the flags, the one before them has two free bits
+        assert_eq!(probability(Vanity::default().with_suffix("000").unwrap()), 1.0);
+        assert_eq!(probability(Vanity::default().with_suffix("a000").unwrap()), 0.25);
+        // A letter has the checksum case half of the time
+        assert_eq!(probability(Vanity::default().with_prefix("a1").unwrap().case_sensitive(true)), 1.0 / 512.0);
+        // Union bound over the 37 positions a 4 characters text can start at
+        assert_eq!(probability(Vanity::default().with_contains("beef").unwrap()), 37.0 / 65536.0);
+        assert_eq!(probability(Vanity::default().with_contains("b").unwrap()), 1.0);
+        assert_eq!(Vanity::default().with_regex("^00").unwrap().probability(BEFORE_INITIALIZE), None);
+    }
+}